@@ -14,6 +14,9 @@ struct PostProcessSettings {
 @group(1) @binding(2)
 var<uniform> settings: PostProcessSettings;
 
+@group(1) @binding(3)
+var<uniform> color: vec4<f32>;
+
 fn tsw(t_diffuse: texture_2d<f32>, s_diffuse: sampler, uv: vec2<f32>) -> vec4<f32> {
     return textureSample(t_diffuse, s_diffuse, uv);
 }
@@ -37,8 +40,8 @@ fn fragment(
 
 
     var mindist = ((uv.x-stored_uv.x)*(uv.x-stored_uv.x)+(uv.y-stored_uv.y)*(uv.y-stored_uv.y));
-    let n = f32(mindist < 12.0);
-    return vec4<f32>(n,0.0,0.0,n);
+    let n = f32(mindist < settings.intensity*settings.intensity);
+    return vec4<f32>(color.rgb,color.a*n);
     //     // ((uv.x-stored_uv.x)*(uv.x-stored_uv.x)+(uv.y-stored_uv.y)*(uv.y-stored_uv.y));
     
     // let a = (settings.intensity+100.0-mindist);
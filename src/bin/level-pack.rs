@@ -0,0 +1,436 @@
+//! Prepares level folders for shipping:
+//! downscales oversized textures, compresses them to KTX2 (Basis UASTC + zstd),
+//! points glTF images at the new files and reports how much texture memory a level takes.
+//!
+//! `cargo run --bin level-pack -- <level> [<level> ...] [options]`
+//!
+//! Compression calls `toktx` from KTX-Software, it has to be in PATH.
+//! Only .gltf with separate image files is handled, .glb levels are left as they are.
+
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use image::{imageops::FilterType, DynamicImage};
+use serde_json::Value;
+
+const USAGE: &str = "\
+level-pack <level> [<level> ...] [options]
+    <level>               folder in assets/levels, or a path to a level folder
+    --all                 every folder in assets/levels
+    --max-size <px>       textures larger than that are downscaled, 2048 by default
+    --no-compress         only downscale and fix URIs, keep png/jpg
+    --remove-originals    delete png/jpg once their .ktx2 is made
+    --report              change nothing, only print texture memory
+
+.glb files (main.glb too) are not supported, their textures are embedded.
+Export the level as glTF Separate (.gltf + .bin + textures) to have them compressed";
+
+struct Options {
+    levels: Vec<PathBuf>,
+    max_size: u32,
+    compress: bool,
+    remove_originals: bool,
+    report_only: bool,
+}
+
+/// Texture memory of one level, in bytes once uploaded to the GPU
+#[derive(Default)]
+struct Report {
+    images: usize,
+    before: u64,
+    after: u64,
+    /// Images inside .glb buffers or data URIs, left as they are
+    embedded: usize,
+    missing: Vec<String>,
+    largest: Vec<(String, u64)>,
+}
+
+fn main() {
+    let options = parse_args();
+
+    for level in options.levels.iter() {
+        println!("== {}", level.display());
+        let mut report = Report::default();
+        let mut done = HashSet::new();
+        let mut originals = HashSet::new();
+
+        let Ok(dir) = level.read_dir() else {
+            println!("not a folder, skipping");
+            continue;
+        };
+        let mut files = dir
+            .filter_map(|p| p.ok())
+            .map(|p| p.path())
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file in files.iter() {
+            match extension(file).as_str() {
+                "gltf" => {
+                    if let Err(err) =
+                        process_gltf(file, &options, &mut report, &mut done, &mut originals)
+                    {
+                        println!("{}: {}", file.display(), err);
+                    }
+                }
+                "glb" => {
+                    // everything is in the binary chunk, nothing to point elsewhere
+                    println!(
+                        "{}: .glb is not supported, export it as glTF Separate to compress its textures",
+                        file.display()
+                    );
+                    report.embedded += 1;
+                }
+                _ => {}
+            }
+        }
+
+        for original in originals.iter() {
+            if let Err(err) = fs::remove_file(original) {
+                println!("couldn't remove {}: {}", original.display(), err);
+            }
+        }
+        print_report(&report, &options);
+    }
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        levels: vec![],
+        max_size: 2048,
+        compress: true,
+        remove_originals: false,
+        report_only: false,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => {
+                let mut all = levels_dir()
+                    .read_dir()
+                    .expect("assets/levels is not found")
+                    .filter_map(|p| p.ok())
+                    .map(|p| p.path())
+                    .filter(|p| p.is_dir())
+                    .collect::<Vec<_>>();
+                all.sort();
+                options.levels.extend(all);
+            }
+            "--max-size" => {
+                options.max_size = args
+                    .next()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or_else(|| usage());
+            }
+            "--no-compress" => options.compress = false,
+            "--remove-originals" => options.remove_originals = true,
+            "--report" => options.report_only = true,
+            "-h" | "--help" => usage(),
+            p if p.starts_with("--") => usage(),
+            p => {
+                let path = PathBuf::from(p);
+                options.levels.push(if path.is_dir() {
+                    path
+                } else {
+                    levels_dir().join(p)
+                });
+            }
+        }
+    }
+    if options.levels.is_empty() {
+        usage();
+    }
+    options
+}
+
+fn usage() -> ! {
+    println!("{}", USAGE);
+    std::process::exit(1);
+}
+
+fn levels_dir() -> PathBuf {
+    let root = env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| env::current_dir().unwrap());
+    root.join("assets/levels")
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|p| p.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn process_gltf(
+    path: &Path,
+    options: &Options,
+    report: &mut Report,
+    done: &mut HashSet<PathBuf>,
+    originals: &mut HashSet<PathBuf>,
+) -> Result<(), String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut gltf = serde_json::from_str::<Value>(&text).map_err(|e| e.to_string())?;
+    let dir = path.parent().unwrap().to_owned();
+    let linear = linear_images(&gltf);
+    let mut changed = false;
+
+    let Some(images) = gltf.get_mut("images").and_then(|p| p.as_array_mut()) else {
+        return Ok(());
+    };
+    for (index, image) in images.iter_mut().enumerate() {
+        let Some(uri) = image.get("uri").and_then(|p| p.as_str()).map(|p| p.to_owned()) else {
+            report.embedded += 1;
+            continue;
+        };
+        if uri.starts_with("data:") {
+            report.embedded += 1;
+            continue;
+        }
+        report.images += 1;
+
+        // exporters on Windows write backslashes, bevy wants forward ones
+        let relative = decode_uri(&uri.replace('\\', "/"));
+        let file = dir.join(&relative);
+        // already pointing at .ktx2, it is made again once the png/jpg next to it is newer
+        let file = match extension(&file).as_str() {
+            "ktx2" if options.compress && !options.report_only => ["png", "jpg", "jpeg"]
+                .iter()
+                .map(|p| file.with_extension(p))
+                .find(|p| p.exists())
+                .unwrap_or(file),
+            _ => file,
+        };
+        let ktx2 = file.with_extension("ktx2");
+
+        let is_source = matches!(extension(&file).as_str(), "png" | "jpg" | "jpeg");
+        if !file.exists() && !(is_source && ktx2.exists()) {
+            report.missing.push(relative.clone());
+            continue;
+        }
+        let before = image_memory(if file.exists() { &file } else { &ktx2 }).unwrap_or(0);
+        report.before += before;
+        if options.report_only || !is_source {
+            report.after += before;
+            report.largest.push((relative.clone(), before));
+            let new_uri = encode_uri(&relative);
+            if !options.report_only && new_uri != uri {
+                image
+                    .as_object_mut()
+                    .unwrap()
+                    .insert("uri".into(), new_uri.into());
+                changed = true;
+            }
+            continue;
+        }
+
+        if done.insert(file.clone()) && file.exists() {
+            let rewritten = prepare_image(&file, options.max_size)?;
+            if options.compress && is_stale(&file, &ktx2) {
+                compress(&file, &ktx2, linear.contains(&index))?;
+                println!("{} -> {}", relative, ktx2.file_name().unwrap().to_string_lossy());
+            } else if rewritten {
+                println!("{} downscaled", relative);
+            }
+        }
+
+        let target = if options.compress { &ktx2 } else { &file };
+        let after = image_memory(target).unwrap_or(0);
+        report.after += after;
+        report.largest.push((relative.clone(), after));
+
+        let new_uri = encode_uri(&target.strip_prefix(&dir).unwrap().to_string_lossy().replace('\\', "/"));
+        let object = image.as_object_mut().unwrap();
+        if new_uri != uri {
+            object.insert("uri".into(), new_uri.into());
+            changed = true;
+        }
+        if options.compress {
+            if object.get("mimeType").and_then(|p| p.as_str()) != Some("image/ktx2") {
+                object.insert("mimeType".into(), "image/ktx2".into());
+                changed = true;
+            }
+            if options.remove_originals && file.exists() {
+                originals.insert(file.clone());
+            }
+        }
+    }
+
+    if changed && !options.report_only {
+        fs::write(path, serde_json::to_string(&gltf).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Images sampled as data rather than color, they must stay linear when compressed
+fn linear_images(gltf: &Value) -> HashSet<usize> {
+    let source_of = |texture: &Value| {
+        texture["index"]
+            .as_u64()
+            .and_then(|p| gltf["textures"][p as usize]["source"].as_u64())
+            .map(|p| p as usize)
+    };
+    let mut linear = HashSet::new();
+    for material in gltf["materials"].as_array().into_iter().flatten() {
+        for texture in [
+            &material["normalTexture"],
+            &material["occlusionTexture"],
+            &material["pbrMetallicRoughness"]["metallicRoughnessTexture"],
+        ] {
+            if let Some(p) = source_of(texture) {
+                linear.insert(p);
+            }
+        }
+    }
+    linear
+}
+
+/// Downscales and drops 16 bit channels in place, `true` if the file was rewritten
+fn prepare_image(file: &Path, max_size: u32) -> Result<bool, String> {
+    let image = image::open(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let is_16_bit = image.color().bytes_per_pixel() > image.color().channel_count();
+    if image.width().max(image.height()) <= max_size && !is_16_bit {
+        return Ok(false);
+    }
+
+    let mut image = if image.width().max(image.height()) > max_size {
+        // keeps the aspect ratio
+        image.resize(max_size, max_size, FilterType::Lanczos3)
+    } else {
+        image
+    };
+    if is_16_bit {
+        image = if image.color().has_alpha() {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+        } else {
+            DynamicImage::ImageRgb8(image.to_rgb8())
+        };
+    }
+    image.save(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    Ok(true)
+}
+
+fn is_stale(source: &Path, target: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|p| p.modified()).ok();
+    match (modified(source), modified(target)) {
+        (Some(source), Some(target)) => source > target,
+        _ => true,
+    }
+}
+
+fn compress(source: &Path, target: &Path, linear: bool) -> Result<(), String> {
+    let mut command = Command::new("toktx");
+    command.args([
+        "--t2",
+        "--encode",
+        "uastc",
+        "--uastc_quality",
+        "2",
+        "--zcmp",
+        "17",
+        "--genmipmap",
+    ]);
+    if linear {
+        command.args(["--assign_oetf", "linear", "--assign_primaries", "none"]);
+    }
+    command.arg(target).arg(source);
+
+    let output = command
+        .output()
+        .map_err(|e| format!("couldn't run toktx, is KTX-Software installed? {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "toktx failed on {}: {}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// Bytes it takes on the GPU: png/jpg go as RGBA8 without mips,
+/// KTX2 with Basis is transcoded to BC7/ASTC 4x4, a byte per pixel, with its mips
+fn image_memory(file: &Path) -> Option<u64> {
+    if extension(file) == "ktx2" {
+        let bytes = fs::read(file).ok()?;
+        if bytes.len() < 44 {
+            return None;
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as u64;
+        let (width, height, levels) = (u32_at(20), u32_at(24).max(1), u32_at(40).max(1));
+        return Some(
+            (0..levels)
+                .map(|l| (width >> l).max(1) * (height >> l).max(1))
+                .sum(),
+        );
+    }
+    let (width, height) = image::image_dimensions(file).ok()?;
+    Some(width as u64 * height as u64 * 4)
+}
+
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(p) = hex.and_then(|p| u8::from_str_radix(p, 16).ok()) {
+                out.push(p);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn encode_uri(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            ' ' => out.push_str("%20"),
+            '%' => out.push_str("%25"),
+            '#' => out.push_str("%23"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / 1024. / 1024.)
+}
+
+fn print_report(report: &Report, options: &Options) {
+    if options.report_only {
+        println!("{} images, {}", report.images, mib(report.before));
+    } else {
+        println!(
+            "{} images, {} -> {}",
+            report.images,
+            mib(report.before),
+            mib(report.after)
+        );
+    }
+    if report.embedded > 0 {
+        println!("{} embedded images are not counted", report.embedded);
+    }
+    for missing in report.missing.iter() {
+        println!("missing: {}", missing);
+    }
+
+    let mut largest = report.largest.clone();
+    largest.sort_by(|a, b| b.1.cmp(&a.1));
+    largest.dedup_by(|a, b| a.0 == b.0);
+    for (name, size) in largest.iter().take(5) {
+        println!("    {:>10}  {}", mib(*size), name);
+    }
+}
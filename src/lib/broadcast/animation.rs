@@ -0,0 +1,577 @@
+use bevy::{
+    animation::RepeatAnimation,
+    gltf::Gltf,
+    prelude::{
+        warn, AnimationClip, AnimationPlayer, Assets, Children, Entity, Events, Handle, Name,
+        Parent, World,
+    },
+};
+use serde_json::{json, Value};
+
+use crate::lib::tools::{
+    events::{ButtonState, CustomEvent},
+    resources::{AnimationMarkers, LoadingSceneInfo},
+};
+
+use super::Action;
+
+/// What happens once a clip reaches its end
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnimationLoop {
+    /// Stops at the end (or at the start, when reversed)
+    Once,
+    /// Wraps around and plays again
+    Loop,
+    /// Turns around at both ends
+    PingPong,
+}
+
+/// Which clip the action plays
+#[derive(Clone, Debug)]
+pub enum ClipRef {
+    /// From `Gltf::named_animations`
+    Named(String),
+    /// Any clip that animates this node, how the old animation actions found them
+    ByNode,
+}
+
+/// Named point of a clip, playback passing it (either direction) fires a signal and/or an event
+#[derive(Clone, Debug)]
+pub struct AnimationMarker {
+    pub name: Option<String>,
+    /// None until it is looked up in `animation_markers.json` by name
+    pub time: Option<f32>,
+    pub signal: Option<u64>,
+    /// `CustomEvent` name, json is `{"marker", "clip", "time", "reversed"}`
+    pub event: Option<String>,
+}
+
+impl AnimationMarker {
+    /// `[time, signal]` or `{"name", "time"?, "signal"?, "event"?}`
+    fn from_extras(value: &Value) -> Option<Self> {
+        if let Some(v) = value.as_array() {
+            return Some(AnimationMarker {
+                name: None,
+                time: Some(v.get(0)?.as_f64()? as f32),
+                signal: Some(v.get(1)?.as_f64()? as u64),
+                event: None,
+            });
+        }
+        let v = value.as_object()?;
+        Some(AnimationMarker {
+            name: v.get("name").and_then(|p| p.as_str()).map(|p| p.to_owned()),
+            time: v.get("time").and_then(|p| p.as_f64()).map(|p| p as f32),
+            signal: v.get("signal").and_then(|p| p.as_u64()),
+            event: v.get("event").and_then(|p| p.as_str()).map(|p| p.to_owned()),
+        })
+    }
+}
+
+enum AnimationCommand {
+    /// 1 - forward, -1 - reversed
+    Play(f32),
+    Pause(bool),
+    Stop,
+    Speed(f32),
+    Seek(f32),
+}
+
+pub struct AnimationAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// Entity holding the `AnimationPlayer`, may be me or some parent/child
+    pub player: Entity,
+    pub clip_ref: ClipRef,
+    pub clip: Option<Handle<AnimationClip>>,
+    pub duration: f32,
+
+    pub mode: AnimationLoop,
+    pub speed: f32,
+    pub direction: f32,
+    pub is_playing: bool,
+    pub is_paused: bool,
+    pub autoplay: bool,
+
+    pub play_signal: Option<u64>,
+    pub reverse_signal: Option<u64>,
+    /// Forward while pressed, reversed once released. Doors and lifts
+    pub hold_signal: Option<u64>,
+    /// Paused while pressed
+    pub pause_signal: Option<u64>,
+    pub stop_signal: Option<u64>,
+    pub speed_signals: Vec<(u64, f32)>,
+    pub seek_signals: Vec<(u64, f32)>,
+
+    pub started_signal: Option<u64>,
+    pub finished_signal: Option<u64>,
+    pub markers: Vec<AnimationMarker>,
+
+    last_seek: f32,
+    /// Clip was just started from its first frame, a marker right at `last_seek` fires too
+    from_start: bool,
+    last_completions: u32,
+    commands: Vec<AnimationCommand>,
+}
+
+impl Default for AnimationAction {
+    fn default() -> Self {
+        AnimationAction {
+            startup: false,
+            name: "animation".into(),
+            me: Entity::PLACEHOLDER,
+            player: Entity::PLACEHOLDER,
+            clip_ref: ClipRef::ByNode,
+            clip: None,
+            duration: 0.,
+            mode: AnimationLoop::Once,
+            speed: 1.,
+            direction: 1.,
+            is_playing: false,
+            is_paused: false,
+            autoplay: false,
+            play_signal: None,
+            reverse_signal: None,
+            hold_signal: None,
+            pause_signal: None,
+            stop_signal: None,
+            speed_signals: vec![],
+            seek_signals: vec![],
+            started_signal: None,
+            finished_signal: None,
+            markers: vec![],
+            last_seek: 0.,
+            from_start: false,
+            last_completions: 0,
+            commands: vec![],
+        }
+    }
+}
+
+/// `[[a, b], ...]` from extras
+fn pairs_from_extras(main: &serde_json::map::Map<String, Value>, name: &str) -> Vec<(f64, f64)> {
+    main.get(name)
+        .and_then(|p| p.as_array())
+        .map(|p| {
+            p.iter()
+                .filter_map(|p| {
+                    let p = p.as_array()?;
+                    Some((p.get(0)?.as_f64()?, p.get(1)?.as_f64()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl Action for AnimationAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let mut a = AnimationAction::default();
+        a.clip_ref = match value.as_str() {
+            Some(name) if !name.is_empty() => ClipRef::Named(name.to_owned()),
+            _ => ClipRef::ByNode,
+        };
+        a.mode = match main.get("#anim_loop").and_then(|p| p.as_str()) {
+            None | Some("once") => AnimationLoop::Once,
+            Some("loop") => AnimationLoop::Loop,
+            Some("ping_pong") => AnimationLoop::PingPong,
+            Some(v) => panic!("#anim_loop is \"once\" | \"loop\" | \"ping_pong\", not {}", v),
+        };
+        if let Some(v) = main.get("#anim_speed").and_then(|p| p.as_f64()) {
+            a.speed = v as f32;
+        }
+        a.autoplay = main
+            .get("#anim_autoplay")
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+
+        a.play_signal = main.get("#anim_play").and_then(|p| p.as_u64());
+        a.reverse_signal = main.get("#anim_reverse").and_then(|p| p.as_u64());
+        a.hold_signal = main.get("#anim_hold").and_then(|p| p.as_u64());
+        a.pause_signal = main.get("#anim_pause").and_then(|p| p.as_u64());
+        a.stop_signal = main.get("#anim_stop").and_then(|p| p.as_u64());
+        a.speed_signals = pairs_from_extras(main, "#anim_speed_signals")
+            .into_iter()
+            .map(|(id, speed)| (id as u64, speed as f32))
+            .collect();
+        a.seek_signals = pairs_from_extras(main, "#anim_seek_signals")
+            .into_iter()
+            .map(|(id, time)| (id as u64, time as f32))
+            .collect();
+
+        a.started_signal = main.get("#anim_started_signal").and_then(|p| p.as_u64());
+        a.finished_signal = main.get("#anim_finished_signal").and_then(|p| p.as_u64());
+        a.markers = main
+            .get("#anim_markers")
+            .and_then(|p| p.as_array())
+            .map(|p| p.iter().filter_map(AnimationMarker::from_extras).collect())
+            .unwrap_or_default();
+        a
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            self.startup = true;
+
+            self.clip = match &self.clip_ref {
+                ClipRef::Named(name) => {
+                    let scene = world.resource::<LoadingSceneInfo>();
+                    world
+                        .resource::<Assets<Gltf>>()
+                        .get(scene.handle.clone_weak())
+                        .and_then(|p| p.named_animations.get(name).cloned())
+                }
+                ClipRef::ByNode => {
+                    let name = world.get::<Name>(me).unwrap().clone();
+                    world
+                        .resource::<Assets<AnimationClip>>()
+                        .iter()
+                        .find(|p| p.1.compatible_with(&name))
+                        .map(|p| Handle::Weak(p.0))
+                }
+            };
+            let Some(clip) = &self.clip else {
+                warn!("action:animation - no clip {:?} found", self.clip_ref);
+                return;
+            };
+            self.duration = world
+                .resource::<Assets<AnimationClip>>()
+                .get(clip)
+                .map(|p| p.duration())
+                .unwrap_or(0.);
+
+            self.player = self.find_player(world);
+            self.resolve_markers(world);
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if self.clip.is_none() {
+            return false;
+        }
+        if self.autoplay {
+            self.autoplay = false;
+            self.commands.push(AnimationCommand::Play(1.));
+        }
+
+        let events = world.resource::<Events<ButtonState>>();
+        let mut reader = events.get_reader();
+        for ev in reader.read(events) {
+            if Some(ev.id) == self.play_signal && ev.is_pressed {
+                self.commands.push(AnimationCommand::Play(1.));
+            }
+            if Some(ev.id) == self.reverse_signal && ev.is_pressed {
+                self.commands.push(AnimationCommand::Play(-1.));
+            }
+            if Some(ev.id) == self.hold_signal {
+                self.commands.push(AnimationCommand::Play(if ev.is_pressed {
+                    1.
+                } else {
+                    -1.
+                }));
+            }
+            if Some(ev.id) == self.pause_signal {
+                self.commands.push(AnimationCommand::Pause(ev.is_pressed));
+            }
+            if Some(ev.id) == self.stop_signal && ev.is_pressed {
+                self.commands.push(AnimationCommand::Stop);
+            }
+            for (id, speed) in self.speed_signals.iter() {
+                if *id == ev.id && ev.is_pressed {
+                    self.commands.push(AnimationCommand::Speed(*speed));
+                }
+            }
+            for (id, time) in self.seek_signals.iter() {
+                if *id == ev.id && ev.is_pressed {
+                    self.commands.push(AnimationCommand::Seek(*time));
+                }
+            }
+        }
+
+        !self.commands.is_empty() || (self.is_playing && !self.is_paused)
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        if world.get::<AnimationPlayer>(self.player).is_none() {
+            return false;
+        }
+
+        for command in std::mem::take(&mut self.commands) {
+            match command {
+                AnimationCommand::Play(direction) => {
+                    // signals are seen for a couple of frames, playing the same way again is a no-op
+                    if !self.is_playing || self.direction != direction || self.is_paused {
+                        self.play(world, direction);
+                        self.is_paused = false;
+                        self.send(world, self.started_signal);
+                    }
+                }
+                AnimationCommand::Pause(pause) => {
+                    let mut player = world.get_mut::<AnimationPlayer>(self.player).unwrap();
+                    if pause {
+                        player.pause();
+                    } else {
+                        player.resume();
+                    }
+                    self.is_paused = pause;
+                }
+                AnimationCommand::Stop => {
+                    world.get_mut::<AnimationPlayer>(self.player).unwrap().pause();
+                    self.is_playing = false;
+                }
+                AnimationCommand::Speed(speed) => {
+                    self.speed = speed;
+                    let clip = self.clip.clone().unwrap();
+                    let mut player = world.get_mut::<AnimationPlayer>(self.player).unwrap();
+                    if player.is_playing_clip(&clip) {
+                        player.set_speed(self.speed * self.direction);
+                    }
+                }
+                AnimationCommand::Seek(time) => {
+                    let time = time.clamp(0., self.duration);
+                    let clip = self.clip.clone().unwrap();
+                    let mut player = world.get_mut::<AnimationPlayer>(self.player).unwrap();
+                    if !player.is_playing_clip(&clip) || player.is_finished() {
+                        // pose has to be sampled from our clip, even if it is not playing
+                        player
+                            .start(clip)
+                            .set_speed(self.speed * self.direction)
+                            .set_repeat(self.repeat());
+                        if !self.is_playing {
+                            player.pause();
+                        }
+                    }
+                    player.seek_to(time);
+                    self.last_seek = time;
+                    self.from_start = false;
+                    self.last_completions = 0;
+                }
+            }
+        }
+
+        if self.is_playing && !self.is_paused {
+            self.poll(world);
+        }
+        true
+    }
+}
+
+impl AnimationAction {
+    /// `one_animation`, `named_animation` and `full_animation` are kept working on top of this one
+    pub fn legacy(kind: &str, value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let mut a = AnimationAction::new(Value::Null, main);
+        match kind {
+            "one_animation" => {
+                a.play_signal = value.as_u64();
+            }
+            "named_animation" => {
+                let v = value.as_array().unwrap();
+                a.play_signal = Some(
+                    v[0].as_str()
+                        .unwrap()
+                        .parse::<u64>()
+                        .expect("named_animation argument 0 should be u64"),
+                );
+                a.clip_ref = ClipRef::Named(v[1].as_str().unwrap().to_owned());
+            }
+            "full_animation" => {
+                let v = value.as_array().unwrap();
+                a.play_signal = Some(v[0].as_f64().unwrap() as u64);
+                if v[1].as_f64().unwrap() as u64 == 0 {
+                    a.mode = AnimationLoop::Loop;
+                }
+                a.stop_signal = Some(v[3].as_f64().unwrap() as u64);
+            }
+            _ => unreachable!(),
+        }
+        a
+    }
+
+    fn repeat(&self) -> RepeatAnimation {
+        match self.mode {
+            AnimationLoop::Loop => RepeatAnimation::Forever,
+            AnimationLoop::Once | AnimationLoop::PingPong => RepeatAnimation::Never,
+        }
+    }
+
+    /// Glb importer puts the player on the root of what is animated, so look around
+    fn find_player(&self, world: &mut World) -> Entity {
+        if world.get::<AnimationPlayer>(self.me).is_some() {
+            return self.me;
+        }
+        let mut current = self.me;
+        while let Some(parent) = world.get::<Parent>(current) {
+            current = parent.get();
+            if world.get::<AnimationPlayer>(current).is_some() {
+                return current;
+            }
+        }
+        let mut stack = vec![self.me];
+        while let Some(entity) = stack.pop() {
+            if world.get::<AnimationPlayer>(entity).is_some() {
+                return entity;
+            }
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter());
+            }
+        }
+        world.entity_mut(self.me).insert(AnimationPlayer::default());
+        self.me
+    }
+
+    /// Markers of the clip from `animation_markers.json` are added, extras may give them a signal
+    /// or an event, or refer to them by name only to take the time from there
+    fn resolve_markers(&mut self, world: &mut World) {
+        let from_file = match (&self.clip_ref, world.get_resource::<AnimationMarkers>()) {
+            (ClipRef::Named(clip), Some(markers)) => markers.0.get(clip).cloned().unwrap_or_default(),
+            _ => vec![],
+        };
+
+        for schema in from_file {
+            if let Some(marker) = self
+                .markers
+                .iter_mut()
+                .find(|p| p.name.as_ref() == Some(&schema.name))
+            {
+                marker.time = marker.time.or(Some(schema.time));
+                marker.signal = marker.signal.or(schema.signal);
+                marker.event = marker.event.take().or(schema.event);
+            } else {
+                self.markers.push(AnimationMarker {
+                    name: Some(schema.name),
+                    time: Some(schema.time),
+                    signal: schema.signal,
+                    event: schema.event,
+                });
+            }
+        }
+
+        self.markers.retain(|p| {
+            if p.time.is_none() {
+                warn!("action:animation - marker {:?} has no time and is not in animation_markers.json", p.name);
+            }
+            p.time.is_some()
+        });
+    }
+
+    /// Continues from the current pose in the given direction
+    fn play(&mut self, world: &mut World, direction: f32) {
+        let clip = self.clip.clone().unwrap();
+        let (duration, speed, repeat) = (self.duration, self.speed, self.repeat());
+        let mut player = world.get_mut::<AnimationPlayer>(self.player).unwrap();
+
+        let mut at = if player.is_playing_clip(&clip) {
+            player.seek_time().clamp(0., duration)
+        } else if direction > 0. {
+            0.
+        } else {
+            duration
+        };
+        // already at the end it is going to, so start over
+        if direction > 0. && at >= duration {
+            at = 0.;
+        } else if direction < 0. && at <= 0. {
+            at = duration;
+        }
+
+        player
+            .start(clip)
+            .seek_to(at)
+            .set_speed(speed * direction)
+            .set_repeat(repeat)
+            .resume();
+
+        self.direction = direction;
+        self.is_playing = true;
+        self.last_seek = at;
+        self.from_start = (direction > 0. && at <= 0.) || (direction < 0. && at >= duration);
+        self.last_completions = 0;
+    }
+
+    /// Fires markers passed since last frame, takes care of the clip ends
+    fn poll(&mut self, world: &mut World) {
+        let clip = self.clip.clone().unwrap();
+        let player = world.get::<AnimationPlayer>(self.player).unwrap();
+        if !player.is_playing_clip(&clip) {
+            // someone else plays on this player now
+            self.is_playing = false;
+            return;
+        }
+        let seek = player.seek_time();
+        let completions = player.completions();
+        let is_finished = player.is_finished();
+        let wrapped = completions > self.last_completions;
+
+        // every piece of the clip that was played, as (from, to, whether `from` itself was played)
+        let start = self.from_start;
+        let segments = match (wrapped && !is_finished, self.direction > 0.) {
+            (false, _) => vec![(self.last_seek, seek.clamp(0., self.duration), start)],
+            (true, true) => vec![(self.last_seek, self.duration, start), (0., seek, true)],
+            (true, false) => vec![(self.last_seek, 0., start), (self.duration, seek, true)],
+        };
+        let passed = self
+            .markers
+            .iter()
+            .filter(|marker| {
+                let time = marker.time.unwrap();
+                segments.iter().any(|(from, to, inclusive)| {
+                    let at_from = *inclusive && time == *from;
+                    if from < to {
+                        (*from < time || at_from) && time <= *to
+                    } else {
+                        *to <= time && (time < *from || at_from)
+                    }
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for marker in passed {
+            self.send(world, marker.signal);
+            if let Some(event) = marker.event {
+                world.send_event(CustomEvent {
+                    name: event,
+                    json_encoded: json!({
+                        "marker": marker.name,
+                        "clip": match &self.clip_ref {
+                            ClipRef::Named(name) => Some(name.clone()),
+                            ClipRef::ByNode => None,
+                        },
+                        "time": marker.time,
+                        "reversed": self.direction < 0.,
+                    })
+                    .to_string(),
+                });
+            }
+        }
+
+        self.last_seek = seek.clamp(0., self.duration);
+        self.from_start = false;
+        self.last_completions = completions;
+
+        if wrapped {
+            self.send(world, self.finished_signal);
+        }
+        if is_finished {
+            match self.mode {
+                AnimationLoop::PingPong => self.play(world, -self.direction),
+                AnimationLoop::Once | AnimationLoop::Loop => self.is_playing = false,
+            }
+        }
+    }
+
+    fn send(&self, world: &mut World, signal: Option<u64>) {
+        if let Some(id) = signal {
+            world.send_event(ButtonState {
+                id,
+                is_pressed: true,
+                just_changed: true,
+            });
+        }
+    }
+}
@@ -25,7 +25,7 @@ impl Default for BallFalling01Action {
         BallFalling01Action {
             startup: false,
             name: "ball_falling_01".into(),
-            hint: Arc::new("Open door".into()),
+            hint: Arc::new("@hint.open_door".into()),
             cell_entity: Entity::PLACEHOLDER,
             ball: Entity::PLACEHOLDER,
             starting_position: Transform::default(),
@@ -0,0 +1,67 @@
+use bevy::prelude::{Entity, GlobalTransform, World};
+use bevy_rapier3d::prelude::RapierContext;
+use serde_json::Value;
+
+use crate::lib::{
+    main_menu::load_settings::save_progress,
+    tools::resources::{LoadingSceneInfo, PlayerResource, Progress},
+};
+
+use super::Action;
+
+pub struct CheckpointAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    pub is_inside: bool,
+}
+
+impl Action for CheckpointAction {
+    fn new(_value: Value, _main: &serde_json::map::Map<String, Value>) -> Self {
+        CheckpointAction {
+            startup: false,
+            name: "checkpoint".into(),
+            me: Entity::PLACEHOLDER,
+            is_inside: false,
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, _world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            self.startup = true;
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        let Some(player) = world.get_resource::<PlayerResource>() else {
+            return false;
+        };
+        let is_inside = world
+            .resource::<RapierContext>()
+            .intersection_pair(player.player_entity, self.me)
+            == Some(true);
+        let entered = is_inside && !self.is_inside;
+        self.is_inside = is_inside;
+        entered
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        let level = world.resource::<LoadingSceneInfo>().name.clone();
+        let position = world.get::<GlobalTransform>(self.me).unwrap().translation();
+
+        let mut progress = world.resource_mut::<Progress>();
+        if progress.is_completed(&level) {
+            return false;
+        }
+        progress.set_checkpoint(&level, position.to_array());
+        save_progress(&progress);
+        true
+    }
+}
@@ -0,0 +1,128 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::{AssetServer, Color, Entity, Events, World},
+    text::TextStyle,
+    time::Time,
+};
+use bevy_rapier3d::prelude::RapierContext;
+use serde_json::Value;
+
+use crate::lib::{
+    main_menu::load_settings::save_progress,
+    tools::{
+        consts::font_names,
+        events::{ButtonState, ProposePopup},
+        resources::{LevelStartTime, LoadingSceneInfo, PlayerResource, Progress, Score},
+    },
+};
+
+use super::Action;
+
+/// How long "level completed" hint stays
+const COMPLETED_HINT_DURATION: Duration = Duration::from_secs(3);
+
+/// Marks the loaded level completed with the time spent and the score, and saves the progress
+pub fn complete_current_level(world: &mut World) {
+    let level = world.resource::<LoadingSceneInfo>().name.clone();
+    let time = (world.resource::<Time>().elapsed() - world.resource::<LevelStartTime>().0).as_secs_f32();
+    let score = *world.resource::<Score>();
+
+    let mut progress = world.resource_mut::<Progress>();
+    progress.complete(&level, time, score);
+    save_progress(&progress);
+}
+
+pub struct CompleteLevelAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// Completes on this signal, or once player walks in if there is none
+    pub signal: Option<u64>,
+    pub is_done: bool,
+    pub hint: Arc<String>,
+    pub hint_until: Option<Duration>,
+    reader: ManualEventReader<ButtonState>,
+}
+
+impl Action for CompleteLevelAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        CompleteLevelAction {
+            startup: false,
+            name: "complete_level".into(),
+            me: Entity::PLACEHOLDER,
+            signal: value
+                .as_u64()
+                .or(value.as_str().and_then(|p| p.parse::<u64>().ok())),
+            is_done: false,
+            hint: Arc::new(
+                main.get("#complete_level_hint")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("@hint.level_completed")
+                    .to_owned(),
+            ),
+            hint_until: None,
+            reader: Default::default(),
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            self.startup = true;
+            // skip the events sent before the level was loaded
+            self.reader = world.resource::<Events<ButtonState>>().get_reader_current();
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if let Some(until) = self.hint_until {
+            if world.resource::<Time>().elapsed() < until {
+                world.send_event(ProposePopup {
+                    text: self.hint.clone(),
+                    priority: 2,
+                    style: TextStyle {
+                        font: world.resource::<AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
+                        font_size: 32.0,
+                        color: Color::GREEN,
+                    },
+                    key: None,
+                });
+            } else {
+                self.hint_until = None;
+            }
+        }
+        if self.is_done {
+            return false;
+        }
+
+        if let Some(id) = self.signal {
+            let events = world.resource::<Events<ButtonState>>();
+            return self.reader.read(events).any(|p| p.id == id && p.is_pressed);
+        }
+
+        let Some(player) = world.get_resource::<PlayerResource>() else {
+            return false;
+        };
+        world
+            .resource::<RapierContext>()
+            .intersection_pair(player.player_entity, self.me)
+            == Some(true)
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        self.is_done = true;
+        complete_current_level(world);
+        if !self.hint.is_empty() {
+            self.hint_until = Some(world.resource::<Time>().elapsed() + COMPLETED_HINT_DURATION);
+        }
+        true
+    }
+}
@@ -0,0 +1,242 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    prelude::{
+        warn, AssetServer, Entity, GlobalTransform, Input, KeyCode, MouseButton, State, Vec3,
+        With, World,
+    },
+    text::TextStyle,
+    time::Time,
+};
+use bevy_rapier3d::prelude::{
+    ExternalImpulse, GravityScale, ReadMassProperties, RigidBody, Sleeping, Velocity,
+};
+use serde_json::Value;
+
+use crate::{
+    lib::{
+        interaction::{Interactable, InteractionFocus, INTERACTION_KEY},
+        tools::{consts::font_names, events::ProposePopup, markers::PlayerCamera},
+    },
+    PlayerState,
+};
+
+use super::Action;
+
+/// Held longer than that, a throw does not get any stronger
+const MAX_CHARGE_TIME: f32 = 1.5;
+/// If the body got stuck this far from where it should be, it slips out of hands
+const MAX_HOLD_STRETCH: f32 = 2.5;
+/// How fast a 1 kg body catches up with the hold point
+const HOLD_RESPONSE: f32 = 12.;
+const ROTATION_SPEED: f32 = 2.;
+
+pub struct GrabbableAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    pub interactable: Interactable,
+    pub held_hint: Arc<String>,
+    /// How far in front of the camera it is held
+    pub hold_distance: f32,
+    /// Spring stiffness, how strongly it is pulled to the hold point
+    pub stiffness: f32,
+    /// Impulse of a fully charged throw
+    pub throw_impulse: f32,
+    pub is_held: bool,
+    pub charge_started: Option<Duration>,
+    pub gravity_scale: f32,
+}
+
+impl Default for GrabbableAction {
+    fn default() -> Self {
+        GrabbableAction {
+            startup: false,
+            name: "grabbable".into(),
+            me: Entity::PLACEHOLDER,
+            interactable: Interactable::new(2., Arc::new("@hint.take".into())),
+            held_hint: Arc::new("@hint.held".into()),
+            hold_distance: 1.5,
+            stiffness: 10.,
+            throw_impulse: 5.,
+            is_held: false,
+            charge_started: None,
+            gravity_scale: 1.,
+        }
+    }
+}
+
+impl Action for GrabbableAction {
+    fn new(_value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let mut a = GrabbableAction::default();
+        a.interactable = Interactable::from_extras(main, 2., a.interactable.hint.clone());
+        if let Some(v) = main.get("#grab_distance").and_then(|p| p.as_f64()) {
+            a.hold_distance = v as f32;
+        }
+        if let Some(v) = main.get("#grab_stiffness").and_then(|p| p.as_f64()) {
+            a.stiffness = v as f32;
+        }
+        if let Some(v) = main.get("#throw_impulse").and_then(|p| p.as_f64()) {
+            a.throw_impulse = v as f32;
+        }
+        a
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.startup {
+            self.me = me;
+
+            let mut entity = world.entity_mut(me);
+            entity.insert((self.interactable.clone(), ReadMassProperties::default()));
+            if !entity.contains::<ExternalImpulse>() {
+                entity.insert(ExternalImpulse::default());
+            }
+            if !entity.contains::<GravityScale>() {
+                entity.insert(GravityScale(1.));
+            }
+
+            self.startup = true;
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if self.is_held {
+            return true;
+        }
+        world.resource::<InteractionFocus>().is_activated(self.me)
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        if !self.is_held {
+            return self.grab(world);
+        }
+
+        if *world.resource::<State<PlayerState>>() != PlayerState::Interactive {
+            self.release(world, Vec3::ZERO);
+            return true;
+        }
+
+        let Some(camera) = world
+            .query_filtered::<&GlobalTransform, With<PlayerCamera>>()
+            .iter(world)
+            .next()
+            .cloned()
+        else {
+            self.release(world, Vec3::ZERO);
+            return true;
+        };
+
+        let now = world.resource::<Time>().elapsed();
+        let dt = world.resource::<Time>().delta_seconds();
+
+        let keys = world.resource::<Input<KeyCode>>();
+        let drop = keys.just_pressed(INTERACTION_KEY);
+        let rotate_yaw = keys.pressed(KeyCode::R);
+        let rotate_pitch = keys.pressed(KeyCode::F);
+
+        let mouse = world.resource::<Input<MouseButton>>();
+        let charge_start = mouse.just_pressed(MouseButton::Left);
+        let charge_end = mouse.just_released(MouseButton::Left);
+
+        if drop {
+            self.release(world, Vec3::ZERO);
+            return true;
+        }
+        if charge_start {
+            self.charge_started = Some(now);
+        }
+        if charge_end {
+            if let Some(started) = self.charge_started {
+                let charge = ((now - started).as_secs_f32() / MAX_CHARGE_TIME).min(1.);
+                self.release(world, camera.forward() * self.throw_impulse * charge);
+                return true;
+            }
+        }
+
+        let target = camera.translation() + camera.forward() * self.hold_distance;
+        let position = world.get::<GlobalTransform>(self.me).unwrap().translation();
+        if (target - position).length() > MAX_HOLD_STRETCH {
+            self.release(world, Vec3::ZERO);
+            return true;
+        }
+
+        let mass = world
+            .get::<ReadMassProperties>(self.me)
+            .map(|p| p.0.mass)
+            .filter(|p| *p > 0.)
+            .unwrap_or(1.);
+        // heavy objects lag behind
+        let response = (dt * HOLD_RESPONSE / mass.sqrt()).min(1.);
+
+        let mut angular = Vec3::ZERO;
+        if rotate_yaw {
+            angular += camera.up() * ROTATION_SPEED;
+        }
+        if rotate_pitch {
+            angular += camera.right() * ROTATION_SPEED;
+        }
+
+        let mut velocity = world.get_mut::<Velocity>(self.me).unwrap();
+        velocity.linvel = velocity
+            .linvel
+            .lerp((target - position) * self.stiffness, response);
+        velocity.angvel = velocity.angvel.lerp(angular, response);
+
+        world.send_event(ProposePopup {
+            text: self.held_hint.clone(),
+            priority: 0,
+            style: TextStyle {
+                font: world.resource::<AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
+                font_size: 32.0,
+                color: bevy::prelude::Color::WHITE,
+            },
+            key: None,
+        });
+        true
+    }
+}
+
+impl GrabbableAction {
+    fn grab(&mut self, world: &mut World) -> bool {
+        if world.get::<RigidBody>(self.me) != Some(&RigidBody::Dynamic) {
+            warn!("action:grabbable is only for rigidbody: Dynamic");
+            return false;
+        }
+
+        if let Some(mut gravity) = world.get_mut::<GravityScale>(self.me) {
+            self.gravity_scale = gravity.0;
+            gravity.0 = 0.;
+        }
+        if let Some(mut sleeping) = world.get_mut::<Sleeping>(self.me) {
+            sleeping.sleeping = false;
+        }
+        if let Some(mut interactable) = world.get_mut::<Interactable>(self.me) {
+            interactable.enabled = false;
+        }
+
+        self.charge_started = None;
+        self.is_held = true;
+        true
+    }
+
+    fn release(&mut self, world: &mut World, impulse: Vec3) {
+        if let Some(mut gravity) = world.get_mut::<GravityScale>(self.me) {
+            gravity.0 = self.gravity_scale;
+        }
+        if let Some(mut external) = world.get_mut::<ExternalImpulse>(self.me) {
+            external.impulse += impulse;
+        }
+        if let Some(mut interactable) = world.get_mut::<Interactable>(self.me) {
+            interactable.enabled = true;
+        }
+
+        self.charge_started = None;
+        self.is_held = false;
+    }
+}
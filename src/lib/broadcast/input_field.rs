@@ -1,23 +1,24 @@
-use std::{time::Duration, sync::Arc};
+use std::sync::Arc;
 
 use bevy::{
-    prelude::{warn, Entity, Events, Name, Transform, Vec3, GlobalTransform, Input, KeyCode, NextState, TextBundle, AssetServer, Visibility},
+    ecs::event::ManualEventReader,
+    prelude::{Entity, Events, Input, KeyCode, NextState, TextBundle, AssetServer, Visibility},
     time::Time,
-    utils::Instant, window::ReceivedCharacter, text::{TextStyle, TextSection}, ui::{BackgroundColor, Style},
+    window::ReceivedCharacter, text::{TextStyle, TextSection}, ui::{BackgroundColor, Style},
 };
-use bevy_rapier3d::prelude::RapierContext;
-use itertools::izip;
 
-use crate::{lib::{tools::{events::{ButtonState, ProposePopup, CustomEvent}, markers::PlayerCameraContainerMarker, consts::font_names}}, PlayerState};
+use crate::{lib::{interaction::{Interactable, InteractionFocus}, tools::{events::CustomEvent, consts::font_names, markers::LevelUiMarker, text_input::TextInput}}, PlayerState};
 
 use super::Action;
 
 pub struct InputFieldAction {
     pub is_started: bool,
     pub name: String,
-    pub enterred_string: String,
+    pub input: TextInput,
+    reader: ManualEventReader<ReceivedCharacter>,
     pub typing_mode: bool,
     pub hint: Arc<String>,
+    pub interactable: Interactable,
     pub stand_entity: Entity,
     pub text_input_field: Entity,
 }
@@ -32,18 +33,28 @@ impl Action for InputFieldAction {
     fn try_startup(&mut self, me: bevy::prelude::Entity, world: &mut bevy::prelude::World) {
         if !self.is_started {
             self.stand_entity = me;
-            
+            world
+                .entity_mut(me)
+                .insert(self.interactable.clone());
+
             let font = world.resource::<AssetServer>();
             let handle = font.load(font_names::NOTO_SANS_BOLD);
-            self.text_input_field = world.spawn((TextBundle {
+            let style = TextStyle { font: handle, font_size: 48., color: bevy::prelude::Color::BLACK };
+            // before the selection, caret, selection, caret, after it
+            let sections = (0..5)
+                .map(|i| TextSection {
+                    value: String::new(),
+                    style: if i == 2 {
+                        TextStyle { color: bevy::prelude::Color::BLUE, ..style.clone() }
+                    } else {
+                        style.clone()
+                    },
+                })
+                .collect();
+            self.text_input_field = world.spawn((LevelUiMarker, TextBundle {
                 background_color: BackgroundColor(bevy::prelude::Color::WHITE),
                 text: bevy::text::Text {
-                    sections: vec![
-                        TextSection {
-                            value: " ".into(),
-                            style: TextStyle { font: handle, font_size: 48., color: bevy::prelude::Color::BLACK }
-                        }
-                    ],
+                    sections,
                     alignment: bevy::text::TextAlignment::Center,
                     ..Default::default()
                 },
@@ -67,10 +78,12 @@ impl Action for InputFieldAction {
         Self: Sized,
     {
         InputFieldAction {
-            hint: Arc::new("Press button".into()),
+            hint: Arc::new("@hint.press_button".into()),
+            interactable: Interactable::from_extras(main, 2., Arc::new("@hint.press_button".into())),
             is_started: false,
             name: value.as_str().unwrap().to_owned(),
-            enterred_string: "".into(),
+            input: TextInput::default(),
+            reader: Default::default(),
             stand_entity: Entity::PLACEHOLDER,
             text_input_field: Entity::PLACEHOLDER,
             typing_mode: false
@@ -79,102 +92,49 @@ impl Action for InputFieldAction {
 
     fn predicate(&mut self, world: &mut bevy::prelude::World) -> bool {
         if !self.typing_mode {
-            //
-            // if !self.can_be_pressed {
-            //     return false;
-            // }
-            
-            let Some(q) = world.iter_entities().find(|p| p.contains::<PlayerCameraContainerMarker>()) else {
-                return false;
-            };
+            return world
+                .resource::<InteractionFocus>()
+                .is_activated(self.stand_entity);
+        }
 
-            let transform = q.get::<GlobalTransform>().unwrap();
+        let characters = world.resource::<Events<ReceivedCharacter>>();
+        let typed = self.reader.read(characters).map(|p| p.char).collect::<String>();
+        self.input.insert(&typed);
 
-            let Some(ctx) = world.get_resource::<RapierContext>() else {
-                return false;
-            };
+        let mut submitted = false;
+        let mut keyboard = world.resource_mut::<Input<KeyCode>>();
+        self.input.handle_keys(&keyboard);
+        let enter = keyboard.just_pressed(KeyCode::Return) || keyboard.just_pressed(KeyCode::NumpadEnter);
+        let escape = keyboard.just_pressed(KeyCode::Escape);
+        // so that E, Escape and the rest don't reach the player
+        keyboard.clear();
 
-            if let Some((entity, toi)) = ctx.cast_ray(
-                transform.translation(),
-                transform.forward(),
-                2.,
-                true,
-                bevy_rapier3d::prelude::QueryFilter {
-                    predicate: Some(&|entity| entity == self.stand_entity),
-                    ..Default::default()
-                },
-            ) {
-                world.send_event(ProposePopup {
-                    text: self.hint.clone(),
-                    priority: 1,
-                    style: TextStyle {
-                        font: world.resource::<bevy::prelude::AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
-                        font_size: 32.0,
-                        color: bevy::prelude::Color::WHITE,
-                    },
-                    key: Some(KeyCode::E),
-                });
-        
-                // let Some(me) = self.button_entity else {
-                //     return false;
-                // };
-        
-                let Some(ok) = world.get_resource::<Input<KeyCode>>() else {
-                    return false;
-                };
-        
-                if ok.just_pressed(KeyCode::E) == false {
-                    return false;
-                }
-                return true;
+        if enter || escape {
+            world.resource_mut::<NextState<PlayerState>>().0 = Some(PlayerState::Interactive);
+            *world.entity_mut(self.text_input_field).get_mut::<Visibility>().unwrap() = Visibility::Hidden;
+            if enter {
+                submitted = true;
+            } else {
+                self.typing_mode = false;
             }
-        } else {
-            let mut a = false;
-            unsafe {
-                let cell = world.as_unsafe_world_cell();
-                let character_event = cell.world().get_resource::<Events<ReceivedCharacter>>().unwrap();
-                let time_res = cell.world().get_resource::<Time>().unwrap();                
+        }
 
-                let mut reader = character_event.get_reader();
-                for char in reader.read(character_event) {
-                    if(char.char.is_alphanumeric() || char.char == ' ' || char.char == ',' || char.char == '.' || char.char == '*' || char.char == '^') {
-                        self.enterred_string.push(char.char);
-                    }
-                }
-                let mut keyboard_event = cell.world_mut().get_resource_mut::<Input<KeyCode>>().unwrap();
-                if keyboard_event.just_pressed(KeyCode::Back) {
-                    self.enterred_string.pop();
-                    println!("Back is pressed {}", self.enterred_string);
-                }
-                if keyboard_event.just_pressed(KeyCode::Return) {
-                    println!("I think it works: {}", &*self.enterred_string);
-                    let mut state = cell.world_mut().resource_mut::<NextState<PlayerState>>();
-                    state.0 = Some(PlayerState::Interactive);
-                    *cell.world_mut().entity_mut(self.text_input_field).get_mut::<Visibility>().unwrap() = Visibility::Hidden;
-                    a = true;
-                }
-                if keyboard_event.just_pressed(KeyCode::Escape) {
-                    let mut state = cell.world_mut().resource_mut::<NextState<PlayerState>>();
-                    state.0 = Some(PlayerState::Interactive);
-                    self.typing_mode = false;
-                    *cell.world_mut().entity_mut(self.text_input_field).get_mut::<Visibility>().unwrap() = Visibility::Hidden;
-                }
-                keyboard_event.clear();
-                let mut text = cell.world_mut().entity_mut(self.text_input_field);
-                let mut text_text = text.get_mut::<bevy::text::Text>().unwrap();
-                text_text.sections[0].value = self.enterred_string.clone();
-                text_text.sections[0].value.push(
-                    if time_res.elapsed().as_secs() % 2 == 0 {
-                        '|'
-                    } else { 
-                        ' '
-                    }
-                );
-                
-                return a;
-            }
+        let caret = if world.resource::<Time>().elapsed().as_secs() % 2 == 0 { "|" } else { " " };
+        let (before, selected, after, caret_first) = self.input.parts();
+        let parts = [
+            before,
+            if caret_first { caret } else { "" },
+            selected,
+            if caret_first { "" } else { caret },
+            after,
+        ];
+        let mut text = world.entity_mut(self.text_input_field);
+        let mut text = text.get_mut::<bevy::text::Text>().unwrap();
+        for (section, part) in text.sections.iter_mut().zip(parts) {
+            section.value = part.to_owned();
         }
-        false
+
+        submitted
     }
 
     fn execute(&mut self, world: &mut bevy::prelude::World) -> bool {
@@ -183,14 +143,17 @@ impl Action for InputFieldAction {
             state.0 = Some(PlayerState::Restricted);
             println!("Entered typing mode");
             *world.entity_mut(self.text_input_field).get_mut::<Visibility>().unwrap() = Visibility::Visible;
+            // the E that opened the field is not typed into it
+            self.reader = world.resource::<Events<ReceivedCharacter>>().get_reader_current();
             self.typing_mode = true;
         } else {
+            println!("{} is answered: {}", self.name, self.input.text);
             world.send_event(CustomEvent {
                 name: self.name.clone(),
-                json_encoded: self.enterred_string.clone()
+                json_encoded: self.input.text.clone()
             });
             self.typing_mode = false;
-            self.enterred_string.clear();
+            self.input.clear();
         }
         true
     }
@@ -1,71 +1,200 @@
-use bevy::prelude::{Handle, AnimationClip, Assets, Entity, warn, Events, AnimationPlayer, Name};
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::{
+        warn, Children, Color, DirectionalLight, Entity, Events, PointLight, SpotLight, World,
+    },
+    time::Time,
+};
+use serde_json::Value;
 
-use crate::lib::tools::events::ButtonState;
+use crate::lib::{
+    interaction,
+    scene_loading::components::LightBaseline,
+    tools::events::ButtonState,
+};
 
 use super::Action;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightMode {
+    /// Every press switches it
+    Toggle,
+    /// On while the signal is pressed, fades in and out
+    Fade,
+    /// Like fade, but flickers while on
+    Flicker,
+}
+
 pub struct LightAnimationAction {
     pub is_started: bool,
-//    pub repeat: bool,
     pub me: Entity,
-    pub was_played: bool,
     pub name: String,
-//    pub animation: Option<Handle<AnimationClip>>,
     pub id: u64,
-    pub from_light: f64,
-    pub to_light: f64,
-    pub longetivity: Duration,
-    pub start: Option<Instant>
+    pub mode: LightMode,
+    /// Light entities under the node, with what they look like when fully on
+    pub lights: Vec<(Entity, LightBaseline)>,
+
+    pub on_intensity: Option<f32>,
+    pub on_color: Option<Color>,
+    pub on_range: Option<f32>,
+    /// Part of the "on" intensity left when it is off
+    pub off_intensity: f32,
+    pub off_color: Option<Color>,
+
+    /// Seconds it takes to fully fade in or out
+    pub longetivity: f32,
+    pub flicker_speed: f32,
+    /// 0..1, how much of the intensity flickering eats
+    pub flicker_amount: f32,
+
+    pub is_on: bool,
+    /// 0 - off, 1 - on
+    pub level: f32,
+    reader: ManualEventReader<ButtonState>,
+}
+
+impl Default for LightAnimationAction {
+    fn default() -> Self {
+        LightAnimationAction {
+            is_started: false,
+            me: Entity::PLACEHOLDER,
+            name: "light".into(),
+            id: 0,
+            mode: LightMode::Toggle,
+            lights: vec![],
+            on_intensity: None,
+            on_color: None,
+            on_range: None,
+            off_intensity: 0.,
+            off_color: None,
+            longetivity: 0.,
+            flicker_speed: 12.,
+            flicker_amount: 0.6,
+            is_on: false,
+            level: 0.,
+            reader: Default::default(),
+        }
+    }
 }
 
 impl Action for LightAnimationAction {
-    fn try_startup(&mut self, me: bevy::prelude::Entity, world: &mut bevy::prelude::World) {
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
         if !self.is_started {
-            self.me = me.clone();
+            self.me = me;
             self.is_started = true;
-            let name = world.get::<Name>(self.me).unwrap().clone();
-            return;
+
+            // glTF puts the light on a child of the node
+            let mut stack = vec![me];
+            while let Some(entity) = stack.pop() {
+                let e = world.entity(entity);
+                let baseline = e.get::<LightBaseline>().cloned().or_else(|| {
+                    if let Some(p) = e.get::<PointLight>() {
+                        Some(LightBaseline { intensity: p.intensity, color: p.color, range: Some(p.range) })
+                    } else if let Some(p) = e.get::<SpotLight>() {
+                        Some(LightBaseline { intensity: p.intensity, color: p.color, range: Some(p.range) })
+                    } else if let Some(p) = e.get::<DirectionalLight>() {
+                        Some(LightBaseline { intensity: p.illuminance, color: p.color, range: None })
+                    } else {
+                        None
+                    }
+                });
+                if let Some(baseline) = baseline {
+                    self.lights.push((entity, baseline));
+                }
+                if let Some(children) = e.get::<Children>() {
+                    stack.extend(children.iter());
+                }
+            }
+            if self.lights.is_empty() {
+                warn!("action:light is on a node without lights");
+            }
+
+            self.level = if self.is_on { 1. } else { 0. };
+            // skip the events sent before the level was loaded
+            self.reader = world.resource::<Events<ButtonState>>().get_reader_current();
+            self.apply(world, 0.);
         }
     }
-    fn new(value: serde_json::Value, main: &serde_json::map::Map<String, serde_json::Value>) -> Self
-        where
-            Self: Sized {
-                warn!("Todo: one_animation repeat");
-
-        let v = value.as_array().unwrap();
-        OneAnimationAction {
-            id: value.as_u64().unwrap(),
-            is_started: false,
-            repeat: false,
-            me: Entity::PLACEHOLDER,
-            was_played: false,
-            name: String::new(),
-            animation: None,
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let mut a = LightAnimationAction::default();
+        a.id = value
+            .as_u64()
+            .or(value.as_str().and_then(|p| p.parse::<u64>().ok()))
+            .expect("action:light should be a signal id");
+        a.mode = match main.get("#light_mode").and_then(|p| p.as_str()) {
+            None | Some("toggle") => LightMode::Toggle,
+            Some("fade") => LightMode::Fade,
+            Some("flicker") => LightMode::Flicker,
+            Some(v) => panic!("#light_mode is \"toggle\" | \"fade\" | \"flicker\", not {}", v),
+        };
+        if a.mode != LightMode::Toggle {
+            a.longetivity = 0.5;
         }
-    }
-    fn predicate(&mut self, world: &mut bevy::prelude::World) -> bool {
-        if self.was_played {
-            return false;
+        if let Some(v) = main.get("#light_duration").and_then(|p| p.as_f64()) {
+            a.longetivity = v.max(0.) as f32;
         }
-        
-        let a = world.get_resource::<Events<ButtonState>>().unwrap();
-        let mut b = a.get_reader();
-        if let Some(a) = b.read(a).find(|&p| p.id == self.id) {
-            self.was_played = true;
-                return true;
+        a.on_intensity = main.get("#light_intensity").and_then(|p| p.as_f64()).map(|p| p as f32);
+        a.on_color = interaction::color_from_extras(main, "#light_color");
+        a.on_range = main.get("#light_range").and_then(|p| p.as_f64()).map(|p| p as f32);
+        if let Some(v) = main.get("#light_off_intensity").and_then(|p| p.as_f64()) {
+            a.off_intensity = v.clamp(0., 1.) as f32;
         }
-        false
-    }
-    fn execute(&mut self, world: &mut bevy::prelude::World) -> bool {
-        if self.animation.is_none() {
-            println!("Animation is not found but played! {:?}", world.get_mut::<Name>(self.me).unwrap());
-            return false;
+        a.off_color = interaction::color_from_extras(main, "#light_off_color");
+        if let Some(v) = main.get("#light_flicker_speed").and_then(|p| p.as_f64()) {
+            a.flicker_speed = v as f32;
+        }
+        if let Some(v) = main.get("#light_flicker_amount").and_then(|p| p.as_f64()) {
+            a.flicker_amount = v.clamp(0., 1.) as f32;
         }
-        let mut player = world.get_mut::<AnimationPlayer>(self.me).unwrap();
-        player.play(self.animation.as_ref().unwrap().clone());
-        if self.repeat {
-            player.repeat();
+        a.is_on = main
+            .get("#light_on")
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+        a
+    }
+    fn predicate(&mut self, world: &mut World) -> bool {
+        let events = world.resource::<Events<ButtonState>>();
+        for ev in self.reader.read(events) {
+            if ev.id != self.id {
+                continue;
+            }
+            match self.mode {
+                LightMode::Toggle => {
+                    if ev.is_pressed {
+                        self.is_on = !self.is_on;
+                    }
+                }
+                LightMode::Fade | LightMode::Flicker => {
+                    self.is_on = ev.is_pressed;
+                }
+            }
         }
+
+        let target = if self.is_on { 1. } else { 0. };
+        self.level != target || (self.mode == LightMode::Flicker && self.level > 0.)
+    }
+    fn execute(&mut self, world: &mut World) -> bool {
+        let dt = world.resource::<Time>().delta_seconds();
+        let target = if self.is_on { 1. } else { 0. };
+        self.level = if self.longetivity <= 0. {
+            target
+        } else if self.level < target {
+            (self.level + dt / self.longetivity).min(target)
+        } else {
+            (self.level - dt / self.longetivity).max(target)
+        };
+
+        let flicker = if self.mode == LightMode::Flicker {
+            let t = world.resource::<Time>().elapsed_seconds() * self.flicker_speed;
+            // a few sines that don't line up look random enough
+            let noise = (t.sin() * 0.5 + (t * 2.3 + 1.7).sin() * 0.3 + (t * 5.1 + 0.4).sin() * 0.2)
+                * 0.5
+                + 0.5;
+            noise * self.flicker_amount
+        } else {
+            0.
+        };
+        self.apply(world, flicker);
         true
     }
     fn name(&self) -> String {
@@ -74,4 +203,44 @@ impl Action for LightAnimationAction {
     fn change_name(&mut self, name: String) {
         self.name = name;
     }
-}
\ No newline at end of file
+}
+
+impl LightAnimationAction {
+    fn apply(&self, world: &mut World, flicker: f32) {
+        let level = self.level * (1. - flicker);
+        for (entity, baseline) in self.lights.iter() {
+            let brightness = self.off_intensity + (1. - self.off_intensity) * level;
+            let intensity = self.on_intensity.unwrap_or(baseline.intensity) * brightness;
+
+            let on_color = self.on_color.unwrap_or(baseline.color);
+            let off_color = self.off_color.unwrap_or(on_color);
+            let color = lerp_color(off_color, on_color, self.level);
+
+            let range = self.on_range.or(baseline.range).map(|p| p * brightness.max(0.01));
+
+            let mut e = world.entity_mut(*entity);
+            if let Some(mut light) = e.get_mut::<PointLight>() {
+                light.intensity = intensity;
+                light.color = color;
+                light.range = range.unwrap();
+            } else if let Some(mut light) = e.get_mut::<SpotLight>() {
+                light.intensity = intensity;
+                light.color = color;
+                light.range = range.unwrap();
+            } else if let Some(mut light) = e.get_mut::<DirectionalLight>() {
+                light.illuminance = intensity;
+                light.color = color;
+            }
+        }
+    }
+}
+
+pub(crate) fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let (from, to) = (from.as_rgba_f32(), to.as_rgba_f32());
+    Color::rgba(
+        from[0] + (to[0] - from[0]) * t,
+        from[1] + (to[1] - from[1]) * t,
+        from[2] + (to[2] - from[2]) * t,
+        from[3] + (to[3] - from[3]) * t,
+    )
+}
@@ -1,20 +1,9 @@
-use std::{rc::Rc, sync::Arc};
+use std::sync::Arc;
 
-use bevy::{
-    prelude::{
-        Children, DetectChangesMut, Entity, Event, Events, GlobalTransform, Input, KeyCode, Name,
-        Parent, Transform, World,
-    },
-    text::TextStyle,
-};
-use bevy_rapier3d::prelude::{CollisionGroups, Group, QueryFilterFlags, RapierContext};
+use bevy::prelude::{Entity, World};
 use serde_json::Value;
 
-use crate::lib::tools::{
-    collision_groups,
-    events::{self, ButtonState, ModifyCollisionGroup, ProposePopup},
-    markers::{PlayerCameraContainerMarker, PlayerParentMarker}, consts::font_names,
-};
+use crate::lib::interaction::{Interactable, InteractionFocus};
 
 use super::Action;
 
@@ -23,6 +12,7 @@ pub struct LinkOpenerAction {
     pub link: Arc<String>,
     pub hint: Arc<String>,
     pub name: String,
+    pub interactable: Interactable,
     pub me: Entity
 }
 
@@ -32,7 +22,8 @@ impl Default for LinkOpenerAction {
             startup: false,
             name: "link_opener".into(),
             link: Arc::new("".into()),
-            hint: Arc::new("Натисність E".into()),
+            hint: Arc::new("@hint.press_e".into()),
+            interactable: Interactable::new(2.8, Arc::new("@hint.press_e".into())),
             me: Entity::PLACEHOLDER
         }
     }
@@ -45,7 +36,8 @@ impl Action for LinkOpenerAction {
             startup: false,
             name: "link_opener".into(),
             link: Arc::new(link.to_string()),
-            hint: Arc::new("Натисніть E".into()),
+            hint: Arc::new("@hint.press_e".into()),
+            interactable: Interactable::from_extras(main, 2.8, Arc::new("@hint.press_e".into())),
             me: Entity::PLACEHOLDER
         }
     }
@@ -53,6 +45,9 @@ impl Action for LinkOpenerAction {
     fn try_startup(&mut self, me: Entity, world: &mut World) {
         if !self.startup {
             self.me = me;
+            world
+                .entity_mut(me)
+                .insert(self.interactable.clone());
             self.startup = true;
         }
     }
@@ -64,69 +59,10 @@ impl Action for LinkOpenerAction {
     }
 
     fn predicate(&mut self, world: &mut World) -> bool {
-        // check if app state is good
-
-        let Some(q) = world.iter_entities().find(|p| p.contains::<PlayerCameraContainerMarker>()) else {
-            return false;
-        };
-
-            let transform = q.get::<GlobalTransform>().unwrap();
-
-            let door = world.get::<GlobalTransform>(self.me).unwrap();
-
-            let d = (transform.translation() - door.translation()).length();
-
-            if d > 2.7 {
-                return false;
-            }
-
-            let Some(ctx) = world.get_resource::<RapierContext>() else {
-                return false;
-            };
-
-            // todo!() move out into a funcion of its own
-
-            if let Some((entity, toi)) = ctx.cast_ray(
-                transform.translation(),
-                transform.forward(),
-                2.8,
-                true,
-                bevy_rapier3d::prelude::QueryFilter {
-                    predicate: Some(&|entity| {
-                        if entity == self.me {
-                            // dbg!(entity);
-                            return true;
-                        }
-                        return false;
-                    }),
-                    ..Default::default()
-                },
-            ) {
-                return true;
-            }
-        
-        // println!("Usage door is {:?}", my_man.clone());
-        return false;
+        world.resource::<InteractionFocus>().is_activated(self.me)
     }
 
     fn execute(&mut self, world: &mut World) -> bool {
-        world.send_event(ProposePopup {
-            text: self.hint.clone(),
-            priority: 1,
-            style: TextStyle {
-                font: world.resource::<bevy::prelude::AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
-                font_size: 32.0,
-                color: bevy::prelude::Color::WHITE,
-            },
-            key: Some(KeyCode::E),
-        });
-        let Some(ok) = world.get_resource::<Input<KeyCode>>() else {
-            return false;
-        };
-
-        if ok.just_pressed(KeyCode::E) == false {
-            return false;
-        }
         webbrowser::open(self.link.as_str());
         true //:D
     }
@@ -0,0 +1,205 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    prelude::{AssetServer, Color, Entity, Events, World},
+    text::TextStyle,
+    time::Time,
+};
+use bevy_rapier3d::prelude::RapierContext;
+use serde_json::Value;
+
+use crate::lib::{
+    scene_loading::level_transition,
+    tools::{
+        config,
+        consts::font_names,
+        events::{ButtonState, ProposePopup},
+        resources::{
+            CurrentLevelConfig, Inventory, LevelTransition, PendingLevel, PlayerResource, Progress,
+            Score,
+        },
+    },
+};
+
+use super::{complete_level::complete_current_level, Action};
+
+/// How long "level is locked" hint stays
+const LOCKED_HINT_DURATION: Duration = Duration::from_secs(3);
+
+pub struct LoadLevelAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// Level folder, `None` for `next` of config.json
+    pub level: Option<String>,
+    /// Goes on this signal, or once player walks in if there is none
+    pub signal: Option<u64>,
+    pub carry_inventory: bool,
+    pub carry_score: bool,
+    /// Current level is marked completed once the portal lets player through
+    pub complete_on_leave: bool,
+    pub fade: Duration,
+    pub is_inside: bool,
+    pub locked_hint_until: Option<Duration>,
+    pub locked_hint: Arc<String>,
+}
+
+impl Action for LoadLevelAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        LoadLevelAction {
+            startup: false,
+            name: "load_level".into(),
+            me: Entity::PLACEHOLDER,
+            level: value
+                .as_str()
+                .filter(|p| !p.is_empty() && *p != "next")
+                .map(|p| p.to_owned()),
+            signal: main.get("#load_level_signal").and_then(|p| p.as_u64()),
+            carry_inventory: main
+                .get("#carry_inventory")
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false),
+            carry_score: main
+                .get("#carry_score")
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false),
+            complete_on_leave: main
+                .get("#complete_on_leave")
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false),
+            fade: Duration::from_secs_f64(
+                main.get("#load_level_fade")
+                    .and_then(|p| p.as_f64())
+                    .unwrap_or(1.)
+                    .max(0.),
+            ),
+            is_inside: false,
+            locked_hint_until: None,
+            locked_hint: Arc::new(String::new()),
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, _world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            self.startup = true;
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if let Some(until) = self.locked_hint_until {
+            if world.resource::<Time>().elapsed() < until {
+                world.send_event(ProposePopup {
+                    text: self.locked_hint.clone(),
+                    priority: 2,
+                    style: TextStyle {
+                        font: world.resource::<AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
+                        font_size: 32.0,
+                        color: Color::ORANGE_RED,
+                    },
+                    key: None,
+                });
+            } else {
+                self.locked_hint_until = None;
+            }
+        }
+        if world.resource::<LevelTransition>().pending.is_some() {
+            return false;
+        }
+
+        if let Some(id) = self.signal {
+            let events = world.resource::<Events<ButtonState>>();
+            let mut reader = events.get_reader();
+            let pressed = reader.read(events).any(|p| p.id == id && p.is_pressed);
+            return pressed;
+        }
+
+        let Some(player) = world.get_resource::<PlayerResource>() else {
+            return false;
+        };
+        let is_inside = world
+            .resource::<RapierContext>()
+            .intersection_pair(player.player_entity, self.me)
+            == Some(true);
+        let entered = is_inside && !self.is_inside;
+        self.is_inside = is_inside;
+        entered
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        let Some(level) = self
+            .level
+            .clone()
+            .or(world.resource::<CurrentLevelConfig>().0.next.clone())
+        else {
+            println!("action:load_level has no level and config.json has no next");
+            return false;
+        };
+
+        let levels = config::read_levels();
+        let completed = world.resource::<Progress>().completed();
+        if !config::is_level_unlocked(&level, &levels, &completed) {
+            let missing = levels
+                .iter()
+                .find(|p| p.0 == level)
+                .map(|p| {
+                    p.1.requires
+                        .iter()
+                        .filter(|p| !completed.contains(*p))
+                        .map(|p| {
+                            levels
+                                .iter()
+                                .find(|l| l.0 == *p)
+                                .map(|l| l.1.name.clone())
+                                .unwrap_or(p.clone())
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            self.locked_hint = Arc::new(if missing.is_empty() {
+                "@hint.level_locked".into()
+            } else {
+                format!("@hint.requires_levels:{}", missing)
+            });
+            self.locked_hint_until = Some(world.resource::<Time>().elapsed() + LOCKED_HINT_DURATION);
+            return false;
+        }
+
+        let level_config = levels.iter().find(|p| p.0 == level).map(|p| &p.1);
+        if let Some(problem) = level_config.and_then(|p| p.version_problem()) {
+            self.locked_hint = Arc::new(problem);
+            self.locked_hint_until = Some(world.resource::<Time>().elapsed() + LOCKED_HINT_DURATION);
+            return false;
+        }
+        // otherwise the fade never clears
+        if config::level_entry(&level, level_config).is_none() {
+            println!("action:load_level: {} has no main.gltf or main.glb", level);
+            self.locked_hint = Arc::new("@hint.level_locked".into());
+            self.locked_hint_until = Some(world.resource::<Time>().elapsed() + LOCKED_HINT_DURATION);
+            return false;
+        }
+
+        if self.complete_on_leave {
+            complete_current_level(world);
+        }
+
+        let pending = PendingLevel {
+            level,
+            inventory: self
+                .carry_inventory
+                .then(|| world.resource::<Inventory>().items.clone()),
+            score: self.carry_score.then(|| *world.resource::<Score>()),
+            requested: false,
+        };
+        world.resource_mut::<LevelTransition>().pending = Some(pending);
+        level_transition::start_fade(world, self.fade);
+        true
+    }
+}
@@ -0,0 +1,219 @@
+use bevy::{
+    ecs::event::ManualEventReader,
+    gltf::Gltf,
+    pbr::StandardMaterial,
+    prelude::{warn, Assets, Children, Color, Entity, Events, Handle, Name, World},
+    time::Time,
+};
+use serde_json::Value;
+
+use crate::lib::{
+    interaction,
+    tools::{events::ButtonState, resources::LoadingSceneInfo},
+};
+
+use super::{light_animation::lerp_color, Action};
+
+/// A mesh under the node, with its own copy of the material
+pub struct MaterialTarget {
+    pub entity: Entity,
+    pub own: Handle<StandardMaterial>,
+    pub base_color: Color,
+    pub emissive: Color,
+}
+
+pub struct MaterialAction {
+    pub is_started: bool,
+    pub me: Entity,
+    pub name: String,
+    pub id: u64,
+    /// Node whose meshes change, me by default
+    pub target_name: Option<String>,
+    pub targets: Vec<MaterialTarget>,
+
+    pub base_color: Option<Color>,
+    pub emissive: Option<Color>,
+    pub emissive_strength: f32,
+    /// Named material from the glTF that replaces the own one
+    pub swap_name: Option<String>,
+    pub swap: Option<Handle<StandardMaterial>>,
+
+    /// Seconds of the tween
+    pub longetivity: f32,
+    /// Stays changed after the first press
+    pub latch: bool,
+
+    pub is_on: bool,
+    /// 0 - as loaded, 1 - changed
+    pub level: f32,
+    reader: ManualEventReader<ButtonState>,
+}
+
+impl Default for MaterialAction {
+    fn default() -> Self {
+        MaterialAction {
+            is_started: false,
+            me: Entity::PLACEHOLDER,
+            name: "material".into(),
+            id: 0,
+            target_name: None,
+            targets: vec![],
+            base_color: None,
+            emissive: None,
+            emissive_strength: 1.,
+            swap_name: None,
+            swap: None,
+            longetivity: 0.,
+            latch: false,
+            is_on: false,
+            level: 0.,
+            reader: Default::default(),
+        }
+    }
+}
+
+impl Action for MaterialAction {
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.is_started {
+            self.me = me;
+            self.is_started = true;
+
+            let root = match &self.target_name {
+                Some(name) => {
+                    let Some(e) = world
+                        .query::<(Entity, &Name)>()
+                        .iter(world)
+                        .find(|p| p.1.as_str() == name)
+                        .map(|p| p.0)
+                    else {
+                        warn!("action:material - no #material_target {} found", name);
+                        return;
+                    };
+                    e
+                }
+                None => me,
+            };
+
+            if let Some(swap_name) = &self.swap_name {
+                let scene = world.resource::<LoadingSceneInfo>();
+                self.swap = world
+                    .resource::<Assets<Gltf>>()
+                    .get(scene.handle.clone_weak())
+                    .and_then(|p| p.named_materials.get(swap_name).cloned());
+                if self.swap.is_none() {
+                    warn!("action:material - no material {} in the glTF", swap_name);
+                }
+            }
+
+            let mut stack = vec![root];
+            while let Some(entity) = stack.pop() {
+                if let Some(handle) = world.get::<Handle<StandardMaterial>>(entity).cloned() {
+                    // materials are shared between meshes, every one of ours gets a copy
+                    let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+                    let material = materials.get(&handle).unwrap().clone();
+                    let (base_color, emissive) = (material.base_color, material.emissive);
+                    let own = materials.add(material);
+                    world.entity_mut(entity).insert(own.clone());
+                    self.targets.push(MaterialTarget {
+                        entity,
+                        own,
+                        base_color,
+                        emissive,
+                    });
+                }
+                if let Some(children) = world.get::<Children>(entity) {
+                    stack.extend(children.iter());
+                }
+            }
+
+            // skip the events sent before the level was loaded
+            self.reader = world.resource::<Events<ButtonState>>().get_reader_current();
+        }
+    }
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let mut a = MaterialAction::default();
+        a.id = value
+            .as_u64()
+            .or(value.as_str().and_then(|p| p.parse::<u64>().ok()))
+            .expect("action:material should be a signal id");
+        a.target_name = main
+            .get("#material_target")
+            .and_then(|p| p.as_str())
+            .map(|p| p.to_owned());
+        a.base_color = interaction::color_from_extras(main, "#material_color");
+        a.emissive = interaction::color_from_extras(main, "#material_emissive");
+        if let Some(v) = main.get("#material_emissive_strength").and_then(|p| p.as_f64()) {
+            a.emissive_strength = v as f32;
+        }
+        a.swap_name = main
+            .get("#material_swap")
+            .and_then(|p| p.as_str())
+            .map(|p| p.to_owned());
+        if let Some(v) = main.get("#material_duration").and_then(|p| p.as_f64()) {
+            a.longetivity = v.max(0.) as f32;
+        }
+        a.latch = main
+            .get("#material_latch")
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+        a
+    }
+    fn predicate(&mut self, world: &mut World) -> bool {
+        let events = world.resource::<Events<ButtonState>>();
+        for ev in self.reader.read(events) {
+            if ev.id == self.id && !(self.latch && self.is_on) {
+                self.is_on = ev.is_pressed;
+            }
+        }
+
+        let target = if self.is_on { 1. } else { 0. };
+        self.level != target
+    }
+    fn execute(&mut self, world: &mut World) -> bool {
+        let dt = world.resource::<Time>().delta_seconds();
+        let target = if self.is_on { 1. } else { 0. };
+        self.level = if self.longetivity <= 0. {
+            target
+        } else if self.level < target {
+            (self.level + dt / self.longetivity).min(target)
+        } else {
+            (self.level - dt / self.longetivity).max(target)
+        };
+
+        if let Some(swap) = &self.swap {
+            // nothing to tween between two materials, it switches halfway
+            for target in self.targets.iter() {
+                let handle = if self.level >= 0.5 {
+                    swap.clone()
+                } else {
+                    target.own.clone()
+                };
+                world.entity_mut(target.entity).insert(handle);
+            }
+            return true;
+        }
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        for target in self.targets.iter() {
+            let Some(material) = materials.get_mut(&target.own) else {
+                continue;
+            };
+            if let Some(color) = self.base_color {
+                material.base_color = lerp_color(target.base_color, color, self.level);
+            }
+            if self.emissive.is_some() || self.emissive_strength != 1. {
+                let color = self.emissive.unwrap_or(target.emissive);
+                let s = self.emissive_strength;
+                let color = Color::rgba(color.r() * s, color.g() * s, color.b() * s, color.a());
+                material.emissive = lerp_color(target.emissive, color, self.level);
+            }
+        }
+        true
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+}
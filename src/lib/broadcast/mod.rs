@@ -1,5 +1,5 @@
 use bevy::{
-    prelude::{Component, Entity, Name, Plugin, PostUpdate, PreUpdate, World, Update},
+    prelude::{Component, Entity, Plugin, PreUpdate, With, World},
     utils::HashMap,
 };
 use serde_json::Value;
@@ -12,12 +12,22 @@ pub mod link_opener;
 // pub mod explosion_test_01;
 pub mod open_door;
 pub mod stand_button;
-pub mod one_animation;
-pub mod full_animation;
-pub mod named_animation;
+pub mod animation;
+pub mod light_animation;
+pub mod material;
+pub mod spawner;
+pub mod section_trigger;
+pub mod load_level;
+pub mod complete_level;
+pub mod checkpoint;
 pub mod delay;
 pub mod test_chamber;
+pub mod quiz;
+pub mod multiple_choice;
 pub mod input_field;
+pub mod grabbable;
+pub mod pickup;
+pub mod requires_item;
 
 pub struct ManagerPlugin {}
 
@@ -27,34 +37,44 @@ impl Plugin for ManagerPlugin {
     }
 }
 
-fn run_all(world: &mut World) {
-    let world = world.as_unsafe_world_cell();
-    unsafe {
-        for (entity, mut actor, name) in world
-            .world_mut()
-            .query::<(Entity, &mut Actor, Option<&Name>)>()
-            .iter_mut(world.world_mut())
-        {
-            for action in actor.0.iter_mut() {
-                action.1.try_startup(entity, world.world_mut());
+pub fn run_all(world: &mut World) {
+    let entities = world
+        .query_filtered::<Entity, With<Actor>>()
+        .iter(world)
+        .collect::<Vec<_>>();
 
-                let pred = action.1.predicate(world.world_mut());
-                if pred {
-                    let exec = action.1.execute(world.world_mut());
-                    // println!(
-                    //     "in entity {} action named {} executed {}",
-                    //     name.and_then(|p| Some(p.to_string()))
-                    //         .unwrap_or_else(|| format!(
-                    //             "{}v{}",
-                    //             entity.index(),
-                    //             entity.generation()
-                    //         )),
-                    //     action.1.name(),
-                    //     exec
-                    // );
-                }
+    for entity in entities {
+        // actions are taken out while they run, so they are free to
+        // insert components into their own entity or even despawn it
+        let Some(mut actions) = world
+            .get_mut::<Actor>(entity)
+            .map(|mut p| std::mem::take(&mut p.0))
+        else {
+            continue;
+        };
+
+        for action in actions.iter_mut() {
+            action.1.try_startup(entity, world);
+
+            let pred = action.1.predicate(world);
+            if pred {
+                let exec = action.1.execute(world);
+                // println!(
+                //     "in entity {:?} action named {} executed {}",
+                //     entity,
+                //     action.1.name(),
+                //     exec
+                // );
+            }
+
+            if world.get_entity(entity).is_none() {
+                break;
             }
         }
+
+        if let Some(mut actor) = world.get_mut::<Actor>(entity) {
+            actor.0.extend(actions);
+        }
     }
 }
 
@@ -71,4 +91,6 @@ pub trait Action: Send + Sync {
     fn try_startup(&mut self, me: Entity, world: &mut World);
     fn predicate(&mut self, world: &mut World) -> bool;
     fn execute(&mut self, world: &mut World) -> bool;
+    /// `RequiresItemAction` tells whether the item is missing, so the action may show it is locked
+    fn set_item_locked(&mut self, _locked: bool, _world: &mut World) {}
 }
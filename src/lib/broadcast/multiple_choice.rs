@@ -0,0 +1,371 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::{AssetServer, Color, Entity, Events, Name, World},
+    text::{Text, TextStyle},
+    time::Time,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde_json::Value;
+
+use crate::lib::tools::{
+    answer::{answers_match, AnswerTolerance},
+    consts::font_names,
+    events::{ButtonState, ProposePopup},
+    questions::{Question, QuestionKind},
+    resources::{Locale, QuestionBank, Score},
+};
+
+use super::{
+    quiz::{place_world_text, question_filter, spawn_world_text},
+    Action,
+};
+
+/// How long the explanation stays after an answer
+const EXPLANATION_DURATION: Duration = Duration::from_secs(6);
+
+/// How far the texts are seen from by default
+const TEXT_DISTANCE: f32 = 6.;
+
+pub struct MultipleChoiceAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// One per option slot, stand_button or collision_button ids
+    pub buttons: Vec<u64>,
+    pub tags: Vec<String>,
+    pub difficulty: Option<(u8, u8)>,
+    /// Given right in extras, otherwise drawn from questions.json
+    pub inline: Option<Question>,
+    pub question: Option<Question>,
+    /// Option put on each button, `None` for spare buttons
+    pub slots: Vec<Option<String>>,
+    /// Names of the nodes the options float over, in button order
+    pub label_names: Vec<String>,
+    /// (node, its text) for each slot that has a label
+    pub labels: Vec<Option<(Entity, Entity)>>,
+    pub text_entity: Entity,
+    pub text_distance: f32,
+    pub right_signal: Option<u64>,
+    pub wrong_signal: Option<u64>,
+    /// Sent once the attempts run out
+    pub failed_signal: Option<u64>,
+    /// 0 for no limit
+    pub attempts: u32,
+    pub attempts_used: u32,
+    /// Slots already answered wrong
+    pub tried: Vec<bool>,
+    pub is_solved: bool,
+    pub chosen: Option<usize>,
+    pub hint: Arc<String>,
+    pub hint_until: Option<Duration>,
+    /// Language the texts were made in
+    pub text_language: String,
+    reader: ManualEventReader<ButtonState>,
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(p)) => vec![p.clone()],
+        Some(Value::Array(p)) => p
+            .iter()
+            .filter_map(|p| p.as_str())
+            .map(|p| p.to_owned())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+impl MultipleChoiceAction {
+    fn is_finished(&self) -> bool {
+        self.is_solved || (self.attempts > 0 && self.attempts_used >= self.attempts)
+    }
+
+    fn is_right(&self, option: &str) -> bool {
+        self.question.as_ref().is_some_and(|q| {
+            q.answers
+                .iter()
+                .any(|p| answers_match(option, p, AnswerTolerance::default()))
+        })
+    }
+
+    /// Puts the options on the buttons in a seeded random order, keeping a right one among them
+    fn shuffle(&mut self, world: &mut World) {
+        let Some(question) = &self.question else {
+            return;
+        };
+        let mut options = question.choices.clone();
+        let mut bank = world.resource_mut::<QuestionBank>();
+        options.shuffle(&mut bank.rng);
+
+        let slots = self.buttons.len();
+        if slots > 0 && options.len() > slots {
+            let right = options.iter().position(|p| {
+                question
+                    .answers
+                    .iter()
+                    .any(|a| answers_match(p, a, AnswerTolerance::default()))
+            });
+            if let Some(right) = right.filter(|p| *p >= slots) {
+                let to = bank.rng.gen_range(0..slots);
+                options.swap(right, to);
+            }
+            println!("action:multiple_choice has {} options for {} buttons", options.len(), slots);
+        }
+
+        self.slots = (0..slots).map(|i| options.get(i).cloned()).collect();
+        self.tried = vec![false; slots];
+    }
+
+    fn update_texts(&mut self, world: &mut World) {
+        let Some(question) = &self.question else {
+            return;
+        };
+        let locale = world.resource::<Locale>();
+        let language = locale.language.clone();
+        let finished = self.is_finished();
+
+        let mut text = locale.resolve(&question.text);
+        // without labels the options are listed in button order
+        if self.labels.iter().all(|p| p.is_none()) {
+            for (i, option) in self.slots.iter().enumerate() {
+                if let Some(option) = option {
+                    text += &format!("\n{}) {}", Question::choice_letter(i), locale.resolve(option));
+                }
+            }
+        }
+        if let (true, Some(explanation)) = (finished, &question.explanation) {
+            text += &format!("\n\n{}", locale.resolve(explanation));
+        }
+
+        let options = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let value = option.as_deref().map(|p| locale.resolve(p)).unwrap_or_default();
+                let color = match option {
+                    Some(p) if finished && self.is_right(p) => Color::DARK_GREEN,
+                    _ if self.tried[i] => Color::GRAY,
+                    _ => Color::BLACK,
+                };
+                (value, color)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(mut main) = world.get_mut::<Text>(self.text_entity) {
+            main.sections[0].value = text;
+        }
+        for (label, (value, color)) in self.labels.iter().zip(options) {
+            let Some((_, entity)) = label else {
+                continue;
+            };
+            if let Some(mut label) = world.get_mut::<Text>(*entity) {
+                label.sections[0].value = value;
+                label.sections[0].style.color = color;
+            }
+        }
+        self.text_language = language;
+    }
+
+    fn show_hint(&mut self, world: &mut World, text: &str) {
+        self.hint = Arc::new(text.to_owned());
+        self.hint_until = Some(world.resource::<Time>().elapsed() + EXPLANATION_DURATION);
+    }
+}
+
+impl Action for MultipleChoiceAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let (tags, difficulty) = question_filter(main);
+        let options = strings(main.get("#choice_options"));
+        let inline = (!options.is_empty()).then(|| Question {
+            id: "inline".into(),
+            text: main
+                .get("#choice_text")
+                .and_then(|p| p.as_str())
+                .unwrap_or_default()
+                .to_owned(),
+            kind: QuestionKind::Choice,
+            answers: strings(main.get("#choice_answers")),
+            choices: options,
+            explanation: main
+                .get("#choice_explanation")
+                .and_then(|p| p.as_str())
+                .map(|p| p.to_owned()),
+            ..Default::default()
+        });
+
+        MultipleChoiceAction {
+            startup: false,
+            name: "multiple_choice".into(),
+            me: Entity::PLACEHOLDER,
+            buttons: value
+                .as_array()
+                .map(|p| p.iter().filter_map(|p| p.as_u64()).collect())
+                .unwrap_or_default(),
+            tags,
+            difficulty,
+            inline,
+            question: None,
+            slots: vec![],
+            label_names: strings(main.get("#choice_labels")),
+            labels: vec![],
+            text_entity: Entity::PLACEHOLDER,
+            text_distance: main
+                .get("#quiz_text_distance")
+                .and_then(|p| p.as_f64())
+                .map(|p| p as f32)
+                .unwrap_or(TEXT_DISTANCE),
+            right_signal: main.get("#choice_right_signal").and_then(|p| p.as_u64()),
+            wrong_signal: main.get("#choice_wrong_signal").and_then(|p| p.as_u64()),
+            failed_signal: main.get("#choice_failed_signal").and_then(|p| p.as_u64()),
+            attempts: main
+                .get("#choice_attempts")
+                .and_then(|p| p.as_u64())
+                .unwrap_or(2) as u32,
+            attempts_used: 0,
+            tried: vec![],
+            is_solved: false,
+            chosen: None,
+            hint: Arc::new(String::new()),
+            hint_until: None,
+            text_language: String::new(),
+            reader: Default::default(),
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if self.startup {
+            return;
+        }
+        self.me = me;
+        self.startup = true;
+        self.reader = world.resource::<Events<ButtonState>>().get_reader_current();
+
+        self.question = match self.inline.clone() {
+            Some(p) => Some(p),
+            None => world.resource_mut::<QuestionBank>().draw(
+                &self.tags,
+                self.difficulty,
+                Some(QuestionKind::Choice),
+            ),
+        };
+        if self.question.is_none() {
+            println!("action:multiple_choice has no #choice_options and no choice questions with tags {:?}", self.tags);
+            return;
+        }
+        if self.buttons.is_empty() {
+            println!("action:multiple_choice has no buttons");
+        }
+        self.shuffle(world);
+
+        self.text_entity = spawn_world_text(world, 28.);
+        let named = world
+            .query::<(Entity, &Name)>()
+            .iter(world)
+            .map(|(entity, name)| (entity, name.as_str().to_owned()))
+            .collect::<Vec<_>>();
+        self.labels = (0..self.slots.len())
+            .map(|i| {
+                let name = self.label_names.get(i)?;
+                match named.iter().find(|p| p.1 == *name) {
+                    Some((anchor, _)) => Some((*anchor, spawn_world_text(world, 24.))),
+                    None => {
+                        println!("action:multiple_choice has no node {} to label", name);
+                        None
+                    }
+                }
+            })
+            .collect();
+        self.update_texts(world);
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if let Some(until) = self.hint_until {
+            if world.resource::<Time>().elapsed() < until {
+                world.send_event(ProposePopup {
+                    text: self.hint.clone(),
+                    priority: 2,
+                    style: TextStyle {
+                        font: world.resource::<AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
+                        font_size: 32.0,
+                        color: if self.is_solved { Color::GREEN } else { Color::ORANGE_RED },
+                    },
+                    key: None,
+                });
+            } else {
+                self.hint_until = None;
+            }
+        }
+        if self.question.is_none() || world.get_entity(self.text_entity).is_none() {
+            return false;
+        }
+
+        if world.resource::<Locale>().language != self.text_language {
+            self.update_texts(world);
+        }
+        place_world_text(world, self.me, self.text_entity, self.text_distance);
+        for (anchor, text) in self.labels.clone().into_iter().flatten() {
+            place_world_text(world, anchor, text, self.text_distance);
+        }
+
+        let events = world.resource::<Events<ButtonState>>();
+        let pressed = self
+            .reader
+            .read(events)
+            .filter(|p| p.is_pressed && p.just_changed)
+            .filter_map(|p| self.buttons.iter().position(|id| *id == p.id))
+            .collect::<Vec<_>>();
+        if self.is_finished() {
+            return false;
+        }
+        self.chosen = pressed
+            .into_iter()
+            .find(|p| self.slots[*p].is_some() && !self.tried[*p]);
+        self.chosen.is_some()
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        let Some(slot) = self.chosen.take() else {
+            return false;
+        };
+        let Some(option) = self.slots[slot].clone() else {
+            return false;
+        };
+        let explanation = self.question.as_ref().and_then(|p| p.explanation.clone());
+
+        let mut signals = vec![];
+        if self.is_right(&option) {
+            self.is_solved = true;
+            world.resource_mut::<Score>().right += 1;
+            signals.push(self.right_signal);
+        } else {
+            self.attempts_used += 1;
+            self.tried[slot] = true;
+            signals.push(self.wrong_signal);
+            if self.is_finished() {
+                world.resource_mut::<Score>().wrong += 1;
+                signals.push(self.failed_signal);
+                self.show_hint(world, explanation.as_deref().unwrap_or("@hint.no_attempts"));
+            } else {
+                self.show_hint(world, explanation.as_deref().unwrap_or("@hint.try_again"));
+            }
+        }
+        for id in signals.into_iter().flatten() {
+            world.send_event(ButtonState {
+                is_pressed: true,
+                just_changed: true,
+                id,
+            });
+        }
+        self.update_texts(world);
+        true
+    }
+}
@@ -1,19 +1,15 @@
-use std::{rc::Rc, sync::Arc};
+use std::{sync::Arc, time::Duration};
 
 use bevy::{
-    prelude::{
-        Children, DetectChangesMut, Entity, Event, Events, GlobalTransform, Input, KeyCode, Name,
-        Parent, Transform, World,
-    },
-    text::TextStyle,
+    prelude::{error, Children, Color, Entity, Events, GlobalTransform, Name, Transform, World},
+    time::Time,
 };
-use bevy_rapier3d::prelude::{CollisionGroups, Group, QueryFilterFlags, RapierContext};
+use bevy_rapier3d::prelude::{Collider, QueryFilter, RapierContext};
 use serde_json::Value;
 
-use crate::lib::tools::{
-    collision_groups,
-    events::{self, ButtonState, ModifyCollisionGroup, ProposePopup},
-    markers::{PlayerCameraContainerMarker, PlayerParentMarker}, consts::font_names,
+use crate::lib::{
+    interaction::{self, Interactable, InteractionFocus, OutlineStyle},
+    tools::events::{self, ButtonState},
 };
 
 use super::Action;
@@ -22,8 +18,23 @@ pub struct OpenDoorAction {
     pub startup: bool,
     pub name: String,
     pub is_opened: bool,
+    pub interactable: Interactable,
     pub opening_strategy: DoorOpenStrategy,
     pub hint: Arc<String>,
+    pub locked_hint: Arc<String>,
+    pub locked_outline: Option<OutlineStyle>,
+    /// Door closes by itself this long after it was opened
+    pub auto_close: Option<Duration>,
+    pub opened_at: Option<Duration>,
+    /// Door stays closed while this signal is low
+    pub lock_signal: Option<u64>,
+    pub is_locked: bool,
+    /// Player lacks the item `#requires_item` asks for, the door still tries to open
+    pub is_item_locked: bool,
+    /// What predicate decided the door should become
+    pub requested: Option<bool>,
+    /// Door wanted to close, but something was in the doorway
+    pub close_pending: bool,
     pub usage_area_entity: Entity,
     pub door_top_entity: Entity,
     pub door_bottom_entity: Entity,
@@ -35,8 +46,18 @@ impl Default for OpenDoorAction {
             startup: false,
             name: "open_door".into(),
             is_opened: false,
+            interactable: Interactable::new(2., Arc::new("@hint.open_door".into())),
             opening_strategy: DoorOpenStrategy::Player,
-            hint: Arc::new("Open door".into()),
+            hint: Arc::new("@hint.open_door".into()),
+            locked_hint: Arc::new("@hint.locked".into()),
+            locked_outline: None,
+            auto_close: None,
+            opened_at: None,
+            lock_signal: None,
+            is_locked: false,
+            is_item_locked: false,
+            requested: None,
+            close_pending: false,
             door_top_entity: Entity::PLACEHOLDER,
             door_bottom_entity: Entity::PLACEHOLDER,
             usage_area_entity: Entity::PLACEHOLDER,
@@ -47,17 +68,52 @@ impl Default for OpenDoorAction {
 impl Action for OpenDoorAction {
     fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
         let mut a = OpenDoorAction::default();
-        if value.as_str() != Some("openable") {
-            println!("Door opening strategy is: {}", value.as_str().unwrap());
-            a.opening_strategy =
-                DoorOpenStrategy::HandledBy(value.as_str().unwrap().parse::<u64>().unwrap());
+        a.opening_strategy = match (value.as_str(), value.as_u64()) {
+            (Some("openable"), _) => DoorOpenStrategy::Player,
+            (Some("obstructed"), _) => DoorOpenStrategy::Obstructed,
+            (Some("broken"), _) => DoorOpenStrategy::Broken,
+            (Some(v), _) => match v.parse::<u64>() {
+                Ok(id) => DoorOpenStrategy::HandledBy(id),
+                Err(_) => {
+                    error!("action:open_door is \"openable\" | \"obstructed\" | \"broken\" | signal id, not {:?}. The door stays shut", v);
+                    DoorOpenStrategy::Broken
+                }
+            },
+            (None, Some(v)) => DoorOpenStrategy::HandledBy(v),
+            _ => {
+                error!("action:open_door is \"openable\" | \"obstructed\" | \"broken\" | signal id, not {}. The door stays shut", value);
+                DoorOpenStrategy::Broken
+            }
+        };
+        println!("Door opening strategy is: {:?}", a.opening_strategy);
+
+        if let Some(hint) = main.get("#door_hint").and_then(|p| p.as_str()) {
+            a.hint = Arc::new(hint.to_owned());
         }
+        if let Some(hint) = main.get("#door_locked_hint").and_then(|p| p.as_str()) {
+            a.locked_hint = Arc::new(hint.to_owned());
+        }
+        a.interactable = Interactable::from_extras(main, 2., a.hint.clone());
+        a.locked_outline = a.interactable.outline.map(|p| OutlineStyle {
+            color: interaction::color_from_extras(main, "#outline_locked_color")
+                .unwrap_or(Color::rgba(1.0, 0.25, 0.25, 1.0)),
+            ..p
+        });
+
+        a.auto_close = main
+            .get("#door_auto_close")
+            .and_then(|p| p.as_f64())
+            .filter(|p| *p > 0.)
+            .map(Duration::from_secs_f64);
+        a.lock_signal = main.get("#door_lock_signal").and_then(|p| p.as_u64());
+        // signal is low until someone presses it
+        a.is_locked = a.lock_signal.is_some();
         a
     }
 
     fn try_startup(&mut self, me: Entity, world: &mut World) {
         if !self.startup {
-            for child in world.get::<Children>(me).unwrap() {
+            for child in world.get::<Children>(me).into_iter().flatten() {
                 if let Some(s) = world.get::<Name>(child.clone()) {
                     // println!("{:?}",s);
                     if s.starts_with("system:Usage_door") {
@@ -70,11 +126,20 @@ impl Action for OpenDoorAction {
                 }
             }
 
-            if self.door_top_entity == Entity::PLACEHOLDER
-                || self.usage_area_entity == Entity::PLACEHOLDER
-                || self.door_bottom_entity == Entity::PLACEHOLDER
-            {
-                panic!();
+            let missing = [
+                (self.usage_area_entity, "system:Usage_door"),
+                (self.door_top_entity, "system:Door_Left"),
+                (self.door_bottom_entity, "system:Door_Right"),
+            ]
+            .into_iter()
+            .filter(|p| p.0 == Entity::PLACEHOLDER)
+            .map(|p| p.1)
+            .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                error!("action:open_door - door has no {} child, it stays shut", missing.join(", "));
+                self.opening_strategy = DoorOpenStrategy::Broken;
+                self.startup = true;
+                return;
             }
 
             world.send_event(events::ModifyCollisionGroup {
@@ -84,6 +149,13 @@ impl Action for OpenDoorAction {
                 override_groups: true,
             });
 
+            if self.opening_strategy == DoorOpenStrategy::Player {
+                world
+                    .entity_mut(self.usage_area_entity)
+                    .insert(self.interactable.clone().with_outline_target(me));
+                self.refresh_interactable(world);
+            }
+
             self.startup = true;
         }
     }
@@ -95,92 +167,70 @@ impl Action for OpenDoorAction {
     }
 
     fn predicate(&mut self, world: &mut World) -> bool {
-        // check if app state is good
-        let my_man = self.usage_area_entity;
-
-        let Some(q) = world.iter_entities().find(|p| p.contains::<PlayerCameraContainerMarker>()) else {
-            return false;
-        };
-
-        if self.opening_strategy == DoorOpenStrategy::Player {
-            let transform = q.get::<GlobalTransform>().unwrap();
-
-            let door = world.get::<GlobalTransform>(my_man).unwrap();
+        let mut requested = None;
 
-            let d = (transform.translation() - door.translation()).length();
+        let was_locked = self.is_locked;
+        {
+            let events = world.resource::<Events<ButtonState>>();
+            let mut reader = events.get_reader();
+            for ev in reader.read(events) {
+                if Some(ev.id) == self.lock_signal {
+                    self.is_locked = !ev.is_pressed;
+                }
+                if self.opening_strategy == DoorOpenStrategy::HandledBy(ev.id) {
+                    requested = Some(ev.is_pressed);
+                }
+            }
+        }
+        if was_locked != self.is_locked {
+            self.refresh_interactable(world);
+        }
 
-            if d > 1.3 {
+        match self.opening_strategy {
+            DoorOpenStrategy::Player => {
+                if world
+                    .resource::<InteractionFocus>()
+                    .is_activated(self.usage_area_entity)
+                {
+                    requested = Some(!self.is_opened);
+                }
+            }
+            DoorOpenStrategy::HandledBy(_) => {}
+            DoorOpenStrategy::Obstructed => {
+                requested = Some(self.is_obstructed(world));
+            }
+            DoorOpenStrategy::Broken => {
                 return false;
             }
+        }
 
-            let Some(ctx) = world.get_resource::<RapierContext>() else {
-                return false;
-            };
-
-            // todo!() move out into a funcion of its own
-
-            if let Some((entity, toi)) = ctx.cast_ray(
-                transform.translation(),
-                transform.forward(),
-                2.,
-                true,
-                bevy_rapier3d::prelude::QueryFilter {
-                    predicate: Some(&|entity| {
-                        if entity == my_man {
-                            // dbg!(entity);
-                            return true;
-                        }
-                        return false;
-                    }),
-                    ..Default::default()
-                },
-            ) {
-                return true;
+        if let (Some(auto_close), Some(opened_at)) = (self.auto_close, self.opened_at) {
+            if world.resource::<Time>().elapsed() - opened_at > auto_close {
+                requested = Some(false);
             }
-        } else if let DoorOpenStrategy::HandledBy(whomm) = self.opening_strategy {
-            //ok
-            return true;
-        } else {
-            println!("Door open strat is not handled");
         }
-        // println!("Usage door is {:?}", my_man.clone());
-        return false;
+        if self.close_pending && requested.is_none() {
+            requested = Some(false);
+        }
+        if self.is_locked {
+            // locked door does not open, and an opened one shuts
+            requested = if self.is_opened { Some(false) } else { None };
+        }
+
+        self.requested = requested;
+        requested.is_some_and(|p| p != self.is_opened)
     }
 
     fn execute(&mut self, world: &mut World) -> bool {
-        let should_be_opened;
-        if self.opening_strategy == DoorOpenStrategy::Player {
-            world.send_event(ProposePopup {
-                text: self.hint.clone(),
-                priority: 1,
-                style: TextStyle {
-                    font: world.resource::<bevy::prelude::AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
-                    font_size: 32.0,
-                    color: bevy::prelude::Color::WHITE,
-                },
-                key: Some(KeyCode::E),
-            });
-
-            let Some(ok) = world.get_resource::<Input<KeyCode>>() else {
-                return false;
-            };
+        let Some(should_be_opened) = self.requested else {
+            return false;
+        };
 
-            if ok.just_pressed(KeyCode::E) == false {
-                return false;
-            }
-            should_be_opened = !self.is_opened;
-        } else if let DoorOpenStrategy::HandledBy(whom) = self.opening_strategy {
-            // cool it is, then
-            let a = world.get_resource::<Events<ButtonState>>().unwrap();
-            let mut b = a.get_reader();
-            if let Some(a) = b.iter(a).find(|&p| p.id == whom) {
-                should_be_opened = a.is_pressed;
-            } else {
-                return false;
-            }
-        } else {
+        if !should_be_opened && self.is_obstructed(world) {
+            self.close_pending = true;
             return false;
         }
+        self.close_pending = false;
 
         if should_be_opened {
             // println!("llol");
@@ -193,6 +243,7 @@ impl Action for OpenDoorAction {
                 .unwrap()
                 .rotate_y(-std::f32::consts::FRAC_PI_2);
             self.is_opened = true;
+            self.opened_at = Some(world.resource::<Time>().elapsed());
         } else {
             world
                 .get_mut::<Transform>(self.door_top_entity)
@@ -203,15 +254,70 @@ impl Action for OpenDoorAction {
                 .unwrap()
                 .rotate_y(std::f32::consts::FRAC_PI_2);
             self.is_opened = false;
+            self.opened_at = None;
         }
         true //:D
     }
+
+    fn set_item_locked(&mut self, locked: bool, world: &mut World) {
+        self.is_item_locked = locked;
+        self.refresh_interactable(world);
+    }
+}
+
+impl OpenDoorAction {
+    /// Anything movable, player included, standing in the usage area
+    fn is_obstructed(&self, world: &mut World) -> bool {
+        let Some(collider) = world.get::<Collider>(self.usage_area_entity).cloned() else {
+            return false;
+        };
+        let transform = world
+            .get::<GlobalTransform>(self.usage_area_entity)
+            .unwrap()
+            .compute_transform();
+        let Some(ctx) = world.get_resource::<RapierContext>() else {
+            return false;
+        };
+
+        let mut obstructed = false;
+        ctx.intersections_with_shape(
+            transform.translation,
+            transform.rotation,
+            &collider,
+            QueryFilter::exclude_fixed()
+                .exclude_sensors()
+                .exclude_collider(self.usage_area_entity),
+            |_| {
+                obstructed = true;
+                false
+            },
+        );
+        obstructed
+    }
+
+    /// Locked doors tell so when looked at
+    fn refresh_interactable(&self, world: &mut World) {
+        let Some(mut interactable) = world.get_mut::<Interactable>(self.usage_area_entity) else {
+            return;
+        };
+        if self.is_locked || self.is_item_locked {
+            interactable.hint = self.locked_hint.clone();
+            interactable.outline = self.locked_outline;
+        } else {
+            interactable.hint = self.hint.clone();
+            interactable.outline = self.interactable.outline;
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// How a door decides to open. Any door refuses to close while something is in the doorway
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum DoorOpenStrategy {
+    /// Player opens and closes it with E
     Player,
+    /// Follows a `ButtonState` with that id
     HandledBy(u64),
+    /// Opens while anything stands in the doorway, closes once it is clear
     Obstructed,
     Broken,
 }
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use bevy::{
+    hierarchy::despawn_with_children_recursive,
+    prelude::{Entity, World},
+};
+use serde_json::Value;
+
+use crate::lib::{
+    interaction::{Interactable, InteractionFocus},
+    tools::{
+        events::ButtonState,
+        resources::{Inventory, InventoryItem},
+    },
+};
+
+use super::Action;
+
+pub struct PickupAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    pub item: InventoryItem,
+    pub interactable: Interactable,
+    /// Fired once the item is picked up
+    pub signal: Option<u64>,
+}
+
+impl Action for PickupAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let id = value
+            .as_str()
+            .map(|p| p.to_owned())
+            .or(value.as_u64().map(|p| p.to_string()))
+            .expect("action:pickup should be an item id");
+        let name = main
+            .get("#pickup_name")
+            .and_then(|p| p.as_str())
+            .unwrap_or(&id)
+            .to_owned();
+
+        PickupAction {
+            startup: false,
+            name: "pickup".into(),
+            me: Entity::PLACEHOLDER,
+            interactable: Interactable::from_extras(
+                main,
+                2.,
+                Arc::new(format!("@hint.pickup:{}", name)),
+            ),
+            item: InventoryItem {
+                id,
+                name,
+                icon: main
+                    .get("#pickup_icon")
+                    .and_then(|p| p.as_str())
+                    .map(|p| p.to_owned()),
+                count: main
+                    .get("#pickup_count")
+                    .and_then(|p| p.as_u64())
+                    .unwrap_or(1) as u32,
+            },
+            signal: main.get("#pickup_signal").and_then(|p| p.as_u64()),
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            world.entity_mut(me).insert(self.interactable.clone());
+            self.startup = true;
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        world.resource::<InteractionFocus>().is_activated(self.me)
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        world.resource_mut::<Inventory>().add(self.item.clone());
+        if let Some(id) = self.signal {
+            world.send_event(ButtonState {
+                id,
+                is_pressed: true,
+                just_changed: true,
+            });
+        }
+        despawn_with_children_recursive(world, self.me);
+        true
+    }
+}
@@ -0,0 +1,283 @@
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::{
+        AssetServer, Camera, Color, Entity, Events, GlobalTransform, TextBundle, Visibility, With,
+        World,
+    },
+    text::{Text, TextStyle},
+    ui::{BackgroundColor, PositionType, Style, UiRect, Val},
+};
+use serde_json::Value;
+
+use crate::lib::tools::{
+    answer::{check_answer, AnswerCheck, AnswerFeedback, AnswerTolerance},
+    consts::{font_names, styles},
+    events::{ButtonState, CustomEvent},
+    markers::{LevelUiMarker, PlayerMainCamera},
+    questions::{Question, QuestionKind},
+    resources::{Locale, QuestionBank, Score},
+};
+
+use super::Action;
+
+/// How far the question text is seen from by default
+const TEXT_DISTANCE: f32 = 6.;
+
+pub struct QuizAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// Name of the input_field answering it
+    pub input_field: String,
+    pub tags: Vec<String>,
+    pub difficulty: Option<(u8, u8)>,
+    pub right_signal: Option<u64>,
+    pub wrong_signal: Option<u64>,
+    pub tolerance: AnswerTolerance,
+    pub feedback: AnswerFeedback,
+    pub text_distance: f32,
+    /// `action:problem`, the question is rolled from problems.json
+    pub generated: bool,
+    pub question: Option<Question>,
+    /// What the answer turned out to be, once it is given
+    pub result: Option<AnswerCheck>,
+    pub text_entity: Entity,
+    /// Language the text was made in
+    pub text_language: String,
+    answers: ManualEventReader<CustomEvent>,
+}
+
+impl QuizAction {
+    fn check(&self, question: &Question, given: &str) -> AnswerCheck {
+        let given = question.expand_choice(given);
+        let tolerance = question.tolerance(self.tolerance);
+        let checks = question
+            .answers
+            .iter()
+            .map(|p| check_answer(given, p, tolerance))
+            .collect::<Vec<_>>();
+        if checks.contains(&AnswerCheck::Right) {
+            AnswerCheck::Right
+        } else {
+            checks.first().copied().unwrap_or(AnswerCheck::WrongValue)
+        }
+    }
+
+    /// Question, its choices and, once answered, the explanation
+    fn text(&self, locale: &Locale) -> String {
+        let Some(question) = &self.question else {
+            return String::new();
+        };
+        let mut text = locale.resolve(&question.text);
+        if question.kind == QuestionKind::Choice {
+            for (i, choice) in question.choices.iter().enumerate() {
+                text += &format!("\n{}) {}", Question::choice_letter(i), locale.resolve(choice));
+            }
+        }
+        if let (Some(_), Some(explanation)) = (self.result, &question.explanation) {
+            text += &format!("\n\n{}", locale.resolve(explanation));
+        }
+        text
+    }
+
+    fn update_text(&mut self, world: &mut World) {
+        let locale = world.resource::<Locale>();
+        let value = self.text(locale);
+        self.text_language = locale.language.clone();
+        let color = match self.result {
+            None => Color::BLACK,
+            Some(AnswerCheck::Right) => Color::DARK_GREEN,
+            Some(_) => Color::MAROON,
+        };
+        if let Some(mut text) = world.get_mut::<Text>(self.text_entity) {
+            text.sections[0].value = value;
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+/// `#quiz_tags` and `#quiz_difficulty`
+pub fn question_filter(main: &serde_json::map::Map<String, Value>) -> (Vec<String>, Option<(u8, u8)>) {
+    let tags = match main.get("#quiz_tags") {
+        Some(Value::String(p)) => vec![p.clone()],
+        Some(Value::Array(p)) => p
+            .iter()
+            .filter_map(|p| p.as_str())
+            .map(|p| p.to_owned())
+            .collect(),
+        _ => vec![],
+    };
+    let difficulty = main
+        .get("#quiz_difficulty")
+        .and_then(|p| p.as_array())
+        .and_then(|p| Some((p.first()?.as_u64()? as u8, p.get(1)?.as_u64()? as u8)));
+    (tags, difficulty)
+}
+
+/// Text floating over something in the level, hidden until `place_world_text` shows it
+pub fn spawn_world_text(world: &mut World, font_size: f32) -> Entity {
+    let font = world.resource::<AssetServer>().load(font_names::NOTO_SANS_SM_BOLD);
+    world
+        .spawn((LevelUiMarker, TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font,
+                    font_size,
+                    color: Color::BLACK,
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                max_width: Val::Percent(30.),
+                padding: UiRect::all(Val::Px(10.)),
+                ..Default::default()
+            },
+            background_color: BackgroundColor(styles::button::LESS_TRANSPARENT_WHITE),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        }))
+        .id()
+}
+
+/// Keeps `text` over `anchor` while it is in front of the camera and closer than `distance`
+pub fn place_world_text(world: &mut World, anchor: Entity, text: Entity, distance: f32) {
+    let Some(position) = world.get::<GlobalTransform>(anchor).map(|p| p.translation()) else {
+        return;
+    };
+    let screen = world
+        .query_filtered::<(&Camera, &GlobalTransform), With<PlayerMainCamera>>()
+        .iter(world)
+        .next()
+        .and_then(|(camera, transform)| {
+            (transform.translation().distance(position) <= distance)
+                .then(|| camera.world_to_viewport(transform, position))
+                .flatten()
+        });
+
+    let Some(mut entity) = world.get_entity_mut(text) else {
+        return;
+    };
+    match screen {
+        Some(screen) => {
+            *entity.get_mut::<Visibility>().unwrap() = Visibility::Visible;
+            let mut style = entity.get_mut::<Style>().unwrap();
+            style.left = Val::Px(screen.x);
+            style.top = Val::Px(screen.y);
+        }
+        None => *entity.get_mut::<Visibility>().unwrap() = Visibility::Hidden,
+    }
+}
+
+impl Action for QuizAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let (tags, difficulty) = question_filter(main);
+        QuizAction {
+            startup: false,
+            name: "quiz".into(),
+            me: Entity::PLACEHOLDER,
+            input_field: value.as_str().unwrap_or_default().to_owned(),
+            tags,
+            difficulty,
+            right_signal: main.get("#quiz_right_signal").and_then(|p| p.as_u64()),
+            wrong_signal: main.get("#quiz_wrong_signal").and_then(|p| p.as_u64()),
+            tolerance: AnswerTolerance::from_extras(main),
+            feedback: AnswerFeedback::from_extras(main),
+            text_distance: main
+                .get("#quiz_text_distance")
+                .and_then(|p| p.as_f64())
+                .map(|p| p as f32)
+                .unwrap_or(TEXT_DISTANCE),
+            generated: false,
+            question: None,
+            result: None,
+            text_entity: Entity::PLACEHOLDER,
+            text_language: String::new(),
+            answers: Default::default(),
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if self.startup {
+            return;
+        }
+        self.me = me;
+        self.startup = true;
+        self.answers = world.resource::<Events<CustomEvent>>().get_reader_current();
+
+        let mut bank = world.resource_mut::<QuestionBank>();
+        self.question = if self.generated {
+            bank.draw_problem(&self.tags, self.difficulty)
+        } else {
+            bank.draw(&self.tags, self.difficulty, None)
+        };
+        if self.question.is_none() {
+            println!(
+                "action:{} has nothing with tags {:?} in {}",
+                self.name,
+                self.tags,
+                if self.generated { "problems.json" } else { "questions.json" }
+            );
+            return;
+        }
+
+        self.text_entity = spawn_world_text(world, 28.);
+        self.update_text(world);
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        let Some(question) = self.question.clone() else {
+            return false;
+        };
+        if world.get_entity(self.text_entity).is_none() {
+            return false;
+        }
+        if world.resource::<Locale>().language != self.text_language {
+            self.update_text(world);
+        }
+        place_world_text(world, self.me, self.text_entity, self.text_distance);
+
+        if self.result.is_some() {
+            return false;
+        }
+        let events = world.resource::<Events<CustomEvent>>();
+        let Some(given) = self
+            .answers
+            .read(events)
+            .find(|p| p.name == self.input_field)
+            .map(|p| p.json_encoded.clone())
+        else {
+            return false;
+        };
+        self.result = Some(self.check(&question, &given));
+        true
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        let Some(check) = self.result else {
+            return false;
+        };
+        let signals = if check == AnswerCheck::Right {
+            world.resource_mut::<Score>().right += 1;
+            vec![self.right_signal]
+        } else {
+            world.resource_mut::<Score>().wrong += 1;
+            vec![self.wrong_signal, self.feedback.signal(check)]
+        };
+        for id in signals.into_iter().flatten() {
+            world.send_event(ButtonState {
+                is_pressed: true,
+                just_changed: true,
+                id,
+            });
+        }
+        self.update_text(world);
+        true
+    }
+}
@@ -0,0 +1,154 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    prelude::{AssetServer, Color, Entity, World},
+    text::TextStyle,
+    time::Time,
+};
+use serde_json::Value;
+
+use crate::lib::tools::{consts::font_names, events::ProposePopup, resources::Inventory};
+
+use super::Action;
+
+/// How long "you need an item" hint stays after a failed attempt
+const LOCKED_HINT_DURATION: Duration = Duration::from_secs(2);
+
+/// Actions `#requires_item` may gate, the first one a node has is gated unless `#requires_item_action` says otherwise
+const GATEABLE: [&str; 6] = ["key_door", "open_door", "stand_button", "pickup", "link", "input_field"];
+
+/// Item id of `#requires_item` (or `#keyed`, `action:key_door`)
+pub fn required_item(main: &serde_json::map::Map<String, Value>) -> Option<String> {
+    main.get("#requires_item")
+        .or(main.get("#keyed"))
+        .or(main.get("action:key_door"))
+        .and_then(|p| {
+            p.as_str()
+                .map(|p| p.to_owned())
+                .or(p.as_u64().filter(|p| *p != 0).map(|p| p.to_string()))
+        })
+}
+
+/// Name of the one action on the node the item is needed for, without "action:"
+pub fn gated_action(main: &serde_json::map::Map<String, Value>) -> Option<&'static str> {
+    match main.get("#requires_item_action").and_then(|p| p.as_str()) {
+        Some(name) => {
+            let found = GATEABLE.iter().find(|p| **p == name).copied();
+            if found.is_none() {
+                println!("#requires_item_action {} is not one of {:?}", name, GATEABLE);
+            }
+            found
+        }
+        None => GATEABLE
+            .iter()
+            .find(|p| main.contains_key(&format!("action:{}", p)))
+            .copied(),
+    }
+}
+
+/// Makes `A`, gated if it is the action `#requires_item` is for
+pub fn build<A: Action + 'static>(
+    name: &str,
+    value: Value,
+    main: &serde_json::map::Map<String, Value>,
+) -> Box<dyn Action> {
+    if required_item(main).is_some() && gated_action(main) == Some(name) {
+        Box::new(RequiresItemAction::<A>::new(value, main))
+    } else {
+        Box::new(A::new(value, main))
+    }
+}
+
+/// Wraps an action, so it only executes while player holds an item
+pub struct RequiresItemAction<A: Action> {
+    pub inner: A,
+    pub item: String,
+    pub consume: bool,
+    /// Once the item was consumed, it is not needed anymore
+    pub unlocked: bool,
+    pub locked_hint: Arc<String>,
+    pub locked_hint_until: Option<Duration>,
+    /// Last told to the inner action, `None` before the first check
+    pub item_locked: Option<bool>,
+}
+
+impl<A: Action> RequiresItemAction<A> {
+    pub fn wrap(inner: A, item: String, main: &serde_json::map::Map<String, Value>) -> Self {
+        let item_name = main
+            .get("#requires_item_name")
+            .and_then(|p| p.as_str())
+            .unwrap_or(&item)
+            .to_owned();
+
+        RequiresItemAction {
+            inner,
+            consume: main
+                .get("#consume_item")
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false),
+            unlocked: false,
+            locked_hint: Arc::new(format!("@hint.requires_item:{}", item_name)),
+            locked_hint_until: None,
+            item_locked: None,
+            item,
+        }
+    }
+}
+
+impl<A: Action> Action for RequiresItemAction<A> {
+    /// `value` is the one of the wrapped action
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let item = required_item(main).unwrap_or_default();
+        RequiresItemAction::wrap(A::new(value, main), item, main)
+    }
+
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        self.inner.try_startup(me, world);
+    }
+    fn change_name(&mut self, name: String) {
+        self.inner.change_name(name);
+    }
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if let Some(until) = self.locked_hint_until {
+            if world.resource::<Time>().elapsed() < until {
+                world.send_event(ProposePopup {
+                    text: self.locked_hint.clone(),
+                    priority: 2,
+                    style: TextStyle {
+                        font: world.resource::<AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
+                        font_size: 32.0,
+                        color: Color::ORANGE_RED,
+                    },
+                    key: None,
+                });
+            } else {
+                self.locked_hint_until = None;
+            }
+        }
+        let locked = !self.unlocked && !world.resource::<Inventory>().has(&self.item);
+        if self.item_locked != Some(locked) {
+            self.item_locked = Some(locked);
+            self.inner.set_item_locked(locked, world);
+        }
+        self.inner.predicate(world)
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        if !self.unlocked {
+            if !world.resource::<Inventory>().has(&self.item) {
+                self.locked_hint_until =
+                    Some(world.resource::<Time>().elapsed() + LOCKED_HINT_DURATION);
+                return false;
+            }
+            if self.consume {
+                world.resource_mut::<Inventory>().take(&self.item);
+                self.unlocked = true;
+            }
+        }
+        self.inner.execute(world)
+    }
+}
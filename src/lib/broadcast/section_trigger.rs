@@ -0,0 +1,97 @@
+use bevy::prelude::{Entity, World};
+use bevy_rapier3d::prelude::RapierContext;
+use serde_json::Value;
+
+use crate::lib::{
+    scene_loading::sections,
+    tools::resources::PlayerResource,
+};
+
+use super::Action;
+
+pub struct SectionTriggerAction {
+    pub startup: bool,
+    pub name: String,
+    pub me: Entity,
+    /// Loaded once player walks in
+    pub load: Vec<String>,
+    /// Unloaded once player walks in
+    pub unload: Vec<String>,
+    /// Sections from `load` are unloaded again when player walks out
+    pub unload_on_exit: bool,
+    pub is_inside: bool,
+}
+
+/// `"name"` or `["name", ...]`
+fn names(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(p)) => vec![p.clone()],
+        Some(Value::Array(p)) => p
+            .iter()
+            .filter_map(|p| p.as_str().map(|p| p.to_owned()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+impl Action for SectionTriggerAction {
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        SectionTriggerAction {
+            startup: false,
+            name: "section_trigger".into(),
+            me: Entity::PLACEHOLDER,
+            load: names(Some(&value)),
+            unload: names(main.get("#section_unload")),
+            unload_on_exit: main
+                .get("#section_unload_on_exit")
+                .and_then(|p| p.as_bool())
+                .unwrap_or(false),
+            is_inside: false,
+        }
+    }
+
+    fn try_startup(&mut self, me: Entity, _world: &mut World) {
+        if !self.startup {
+            self.me = me;
+            self.startup = true;
+        }
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn predicate(&mut self, world: &mut World) -> bool {
+        let Some(player) = world.get_resource::<PlayerResource>() else {
+            return false;
+        };
+        let is_inside = world
+            .resource::<RapierContext>()
+            .intersection_pair(player.player_entity, self.me)
+            == Some(true);
+
+        if is_inside != self.is_inside {
+            self.is_inside = is_inside;
+            return true;
+        }
+        false
+    }
+
+    fn execute(&mut self, world: &mut World) -> bool {
+        if self.is_inside {
+            for name in self.unload.iter() {
+                sections::unload_section(world, name);
+            }
+            for name in self.load.iter() {
+                sections::load_section(world, name);
+            }
+        } else if self.unload_on_exit {
+            for name in self.load.iter() {
+                sections::unload_section(world, name);
+            }
+        }
+        true
+    }
+}
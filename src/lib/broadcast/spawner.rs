@@ -0,0 +1,288 @@
+use std::{any::TypeId, collections::VecDeque, path::PathBuf, time::Duration};
+
+use bevy::{
+    ecs::{event::ManualEventReader, reflect::ReflectComponent},
+    gltf::Gltf,
+    hierarchy::{despawn_with_children_recursive, BuildWorldChildren, Parent},
+    prelude::{
+        error, warn, AppTypeRegistry, AssetServer, Assets, Children, Entity, Events,
+        GlobalTransform, Handle, Name, Transform, Vec3, World,
+    },
+    scene::{Scene, SceneBundle},
+    time::Time,
+};
+use bevy_rapier3d::prelude::{RigidBody, Velocity};
+use serde_json::Value;
+
+use crate::lib::{
+    scene_loading::components::SubSceneMarker,
+    tools::{events::ButtonState, resources::LoadingSceneInfo},
+};
+
+use super::Action;
+
+/// Rigidbodies of a fresh copy show up a couple of frames later, it waits that long for them
+const VELOCITY_WAIT: Duration = Duration::from_secs(2);
+
+pub enum SpawnSource {
+    /// From `Gltf::named_scenes` of the level
+    Scene(String),
+    /// A node of the level and everything under it
+    Node(String),
+    /// Another glTF in the level folder, its first scene
+    File(String),
+}
+
+pub struct SpawnerAction {
+    pub is_started: bool,
+    pub me: Entity,
+    pub name: String,
+    pub id: u64,
+    /// `None` if extras name none, the spawner does nothing then
+    pub source: Option<SpawnSource>,
+    pub scene: Option<Handle<Scene>>,
+    /// In spawner's own space
+    pub velocity: Vec3,
+    /// 0 - no limit, otherwise the oldest copy is removed
+    pub max_alive: usize,
+    pub alive: VecDeque<Entity>,
+    /// Copies that did not get their velocity yet
+    pub pending: Vec<(Entity, Duration)>,
+    /// Presses since last execute
+    pub to_spawn: usize,
+    reader: ManualEventReader<ButtonState>,
+}
+
+impl Action for SpawnerAction {
+    fn try_startup(&mut self, me: Entity, world: &mut World) {
+        if !self.is_started {
+            self.me = me;
+            self.is_started = true;
+
+            let level = world.resource::<LoadingSceneInfo>().name.clone();
+            self.scene = match &self.source {
+                Some(SpawnSource::Scene(name)) => {
+                    let scene = world.resource::<LoadingSceneInfo>();
+                    world
+                        .resource::<Assets<Gltf>>()
+                        .get(scene.handle.clone_weak())
+                        .and_then(|p| p.named_scenes.get(name).cloned())
+                }
+                Some(SpawnSource::Node(name)) => node_scene(world, name),
+                Some(SpawnSource::File(file)) => {
+                    let path = PathBuf::from("levels").join(level).join(file);
+                    Some(
+                        world
+                            .resource::<AssetServer>()
+                            .load(format!("{}#Scene0", path.display())),
+                    )
+                }
+                None => None,
+            };
+            if self.scene.is_none() && self.source.is_some() {
+                warn!("action:spawner - nothing to spawn, no such scene or node");
+            }
+
+            // skip the events sent before the level was loaded
+            self.reader = world.resource::<Events<ButtonState>>().get_reader_current();
+        }
+    }
+    fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
+        let id = value
+            .as_u64()
+            .or(value.as_str().and_then(|p| p.parse::<u64>().ok()))
+            .expect("action:spawner should be a signal id");
+
+        let source = if let Some(name) = main.get("#spawn_scene").and_then(|p| p.as_str()) {
+            Some(SpawnSource::Scene(name.to_owned()))
+        } else if let Some(name) = main.get("#spawn_node").and_then(|p| p.as_str()) {
+            Some(SpawnSource::Node(name.to_owned()))
+        } else if let Some(file) = main.get("#spawn_file").and_then(|p| p.as_str()) {
+            Some(SpawnSource::File(file.to_owned()))
+        } else {
+            error!("action:spawner needs #spawn_scene, #spawn_node or #spawn_file, it does nothing");
+            None
+        };
+
+        let velocity = main
+            .get("#spawn_velocity")
+            .and_then(|p| p.as_array())
+            .map(|p| {
+                Vec3::new(
+                    p[0].as_f64().unwrap() as f32,
+                    p[1].as_f64().unwrap() as f32,
+                    p[2].as_f64().unwrap() as f32,
+                )
+            })
+            .unwrap_or(Vec3::ZERO);
+
+        SpawnerAction {
+            is_started: false,
+            me: Entity::PLACEHOLDER,
+            name: "spawner".into(),
+            id,
+            source,
+            scene: None,
+            velocity,
+            max_alive: main
+                .get("#spawn_max")
+                .and_then(|p| p.as_u64())
+                .unwrap_or(0) as usize,
+            alive: VecDeque::new(),
+            pending: vec![],
+            to_spawn: 0,
+            reader: Default::default(),
+        }
+    }
+    fn predicate(&mut self, world: &mut World) -> bool {
+        if self.scene.is_none() {
+            return false;
+        }
+        let events = world.resource::<Events<ButtonState>>();
+        self.to_spawn += self
+            .reader
+            .read(events)
+            .filter(|p| p.id == self.id && p.is_pressed)
+            .count();
+        self.to_spawn > 0 || !self.pending.is_empty()
+    }
+    fn execute(&mut self, world: &mut World) -> bool {
+        // copies may be destroyed by other actions
+        self.alive.retain(|p| world.get_entity(*p).is_some());
+        self.pending.retain(|p| world.get_entity(p.0).is_some());
+
+        for _ in 0..std::mem::take(&mut self.to_spawn) {
+            self.spawn(world);
+        }
+        self.apply_velocity(world);
+        true
+    }
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn change_name(&mut self, name: String) {
+        self.name = name;
+    }
+}
+
+impl SpawnerAction {
+    /// Puts a fresh copy at the spawner, `gltf_load_extras` takes care of it once the scene is in
+    pub fn spawn(&mut self, world: &mut World) {
+        let transform = world
+            .get::<GlobalTransform>(self.me)
+            .unwrap()
+            .compute_transform();
+
+        if self.max_alive != 0 {
+            while self.alive.len() >= self.max_alive {
+                let oldest = self.alive.pop_front().unwrap();
+                despawn_with_children_recursive(world, oldest);
+            }
+        }
+
+        let copy = world
+            .spawn((
+                SceneBundle {
+                    scene: self.scene.clone().unwrap(),
+                    transform,
+                    ..Default::default()
+                },
+                SubSceneMarker,
+                Name::new("Spawned copy"),
+            ))
+            .id();
+        self.alive.push_back(copy);
+        if self.velocity != Vec3::ZERO {
+            self.pending
+                .push((copy, world.resource::<Time>().elapsed() + VELOCITY_WAIT));
+        }
+    }
+
+    fn apply_velocity(&mut self, world: &mut World) {
+        let now = world.resource::<Time>().elapsed();
+        let velocity = world
+            .get::<GlobalTransform>(self.me)
+            .unwrap()
+            .compute_transform()
+            .rotation
+            * self.velocity;
+
+        let mut done = vec![];
+        for (copy, until) in self.pending.iter() {
+            let mut bodies = vec![];
+            let mut stack = vec![*copy];
+            while let Some(entity) = stack.pop() {
+                if world.get::<RigidBody>(entity) == Some(&RigidBody::Dynamic)
+                    && world.get::<Velocity>(entity).is_some()
+                {
+                    bodies.push(entity);
+                }
+                if let Some(children) = world.get::<Children>(entity) {
+                    stack.extend(children.iter());
+                }
+            }
+
+            for body in bodies.iter() {
+                world.get_mut::<Velocity>(*body).unwrap().linvel = velocity;
+            }
+            if !bodies.is_empty() || now > *until {
+                done.push(*copy);
+            }
+        }
+        self.pending.retain(|p| !done.contains(&p.0));
+    }
+}
+
+/// The node named `name` with everything under it, copied out of the level's scenes into a scene of its own.
+/// Copies keep their extras, so `gltf_load_extras` handles them like the rest of the level
+fn node_scene(world: &mut World, name: &str) -> Option<Handle<Scene>> {
+    let copy = {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let gltf = world
+            .resource::<Assets<Gltf>>()
+            .get(&world.resource::<LoadingSceneInfo>().handle)?;
+        let scenes = world.resource::<Assets<Scene>>();
+        let (source, root) = gltf.scenes.iter().filter_map(|p| scenes.get(p)).find_map(|scene| {
+            scene
+                .world
+                .iter_entities()
+                .find(|p| p.get::<Name>().is_some_and(|p| p.as_str() == name))
+                .map(|p| (&scene.world, p.id()))
+        })?;
+
+        let mut copy = World::new();
+        let mut stack = vec![(root, None)];
+        while let Some((entity, parent)) = stack.pop() {
+            let new = copy.spawn_empty().id();
+            // hierarchy is rebuilt below, it points to entities of the source world
+            for component in source.entity(entity).archetype().components() {
+                let Some(type_id) = source.components().get_info(component).and_then(|p| p.type_id()) else {
+                    continue;
+                };
+                if type_id == TypeId::of::<Parent>() || type_id == TypeId::of::<Children>() {
+                    continue;
+                }
+                if let Some(reflect) = registry.get(type_id).and_then(|p| p.data::<ReflectComponent>()) {
+                    reflect.copy(source, &mut copy, entity, new);
+                }
+            }
+            match parent {
+                Some(parent) => {
+                    copy.entity_mut(parent).push_children(&[new]);
+                }
+                // it is put at the spawner, not where the node is
+                None => {
+                    if let Some(mut transform) = copy.get_mut::<Transform>(new) {
+                        transform.translation = Vec3::ZERO;
+                    }
+                }
+            }
+            if let Some(children) = source.get::<Children>(entity) {
+                stack.extend(children.iter().map(|p| (*p, Some(new))));
+            }
+        }
+        copy
+    };
+    Some(world.resource_mut::<Assets<Scene>>().add(Scene::new(copy)))
+}
@@ -1,20 +1,14 @@
-use std::{rc::Rc, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use bevy::{
-    prelude::{
-        Children, Entity, Event, GlobalTransform, Input, KeyCode, Name, Parent, Transform, World,
-    },
-    text::TextStyle,
+    prelude::{Entity, World},
     time::Time,
-    utils::Instant,
 };
-use bevy_rapier3d::prelude::{CollisionGroups, Group, QueryFilterFlags, RapierContext};
 use serde_json::Value;
 
-use crate::lib::tools::{
-    collision_groups,
-    events::{self, ButtonState, ModifyCollisionGroup, ProposePopup},
-    markers::{PlayerCameraContainerMarker, PlayerParentMarker}, consts::font_names,
+use crate::lib::{
+    interaction::{Interactable, InteractionFocus},
+    tools::events::ButtonState,
 };
 
 use super::Action;
@@ -27,6 +21,7 @@ pub struct StandButtonAction {
     pub cooldown: Duration,
     pub can_be_pressed: bool,
     pub hint: Arc<String>,
+    pub interactable: Interactable,
     pub stand_entity: Entity,
     // pub button_entity: Entity,
 
@@ -39,7 +34,8 @@ impl Default for StandButtonAction {
         StandButtonAction {
             startup: false,
             name: "stand_button".into(),
-            hint: Arc::new("Press button".into()),
+            hint: Arc::new("@hint.press_button".into()),
+            interactable: Interactable::new(2., Arc::new("@hint.press_button".into())),
             can_be_pressed: true,
             when_pressed: None,
             press_longetivity: Duration::from_secs_f32(2.),
@@ -55,6 +51,7 @@ impl Action for StandButtonAction {
     fn new(value: Value, main: &serde_json::map::Map<String, Value>) -> Self {
         let mut a = StandButtonAction::default();
         a.retarget_index = value.as_u64().unwrap();
+        a.interactable = Interactable::from_extras(main, 2., a.hint.clone());
         a.cooldown =
             Duration::from_secs_f32(main.get("#cooldown").unwrap().as_f64().unwrap() as f32);
         a.press_longetivity = Duration::from_secs_f32(
@@ -66,6 +63,9 @@ impl Action for StandButtonAction {
     fn try_startup(&mut self, me: Entity, world: &mut World) {
         if !self.startup {
             self.stand_entity = me;
+            world
+                .entity_mut(me)
+                .insert(self.interactable.clone());
             self.startup = true;
         }
     }
@@ -107,72 +107,26 @@ impl Action for StandButtonAction {
             // self.when_pressed = None;
             self.is_pressed = false;
         }
-        if !self.can_be_pressed {
-            return false;
-        }
 
-        let Some(q) = world.iter_entities().find(|p| p.contains::<PlayerCameraContainerMarker>()) else {
-            return false;
-        };
+        let on_cooldown = self.when_pressed.is_some()
+            && (world.get_resource::<Time>().unwrap().elapsed() - self.when_pressed.unwrap()
+                < self.cooldown
+                || self.cooldown.is_zero());
 
-        let transform = q.get::<GlobalTransform>().unwrap();
+        if let Some(mut interactable) = world.get_mut::<Interactable>(self.stand_entity) {
+            interactable.enabled = self.can_be_pressed && !on_cooldown;
+        }
 
-        let Some(ctx) = world.get_resource::<RapierContext>() else {
+        if !self.can_be_pressed || on_cooldown {
             return false;
-        };
-
-        if let Some((entity, toi)) = ctx.cast_ray(
-            transform.translation(),
-            transform.forward(),
-            2.,
-            true,
-            bevy_rapier3d::prelude::QueryFilter {
-                predicate: Some(&|entity| entity == self.stand_entity),
-                ..Default::default()
-            },
-        ) {
-            return true;
         }
 
-        false
+        world
+            .resource::<InteractionFocus>()
+            .is_activated(self.stand_entity)
     }
 
     fn execute(&mut self, world: &mut World) -> bool {
-        if self.when_pressed.is_some()
-            && (world
-                .get_resource::<Time>()
-                .unwrap()
-                .elapsed() -
-                self.when_pressed.unwrap()
-                < self.cooldown
-                || self.cooldown.is_zero())
-        {
-            return false;
-        }
-
-        world.send_event(ProposePopup {
-            text: self.hint.clone(),
-            priority: 1,
-            style: TextStyle {
-                font: world.resource::<bevy::prelude::AssetServer>().load(font_names::NOTO_SANS_MEDIUM),
-                font_size: 32.0,
-                color: bevy::prelude::Color::WHITE,
-            },
-            key: Some(KeyCode::E),
-        });
-
-        // let Some(me) = self.button_entity else {
-        //     return false;
-        // };
-
-        let Some(ok) = world.get_resource::<Input<KeyCode>>() else {
-            return false;
-        };
-
-        if ok.just_pressed(KeyCode::E) == false {
-            return false;
-        }
-
         world.send_event(ButtonState {
             id: self.retarget_index,
             is_pressed: true,
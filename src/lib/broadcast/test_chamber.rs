@@ -1,14 +1,15 @@
 use std::time::Duration;
 
 use bevy::{
-    prelude::{warn, Entity, Events, Name, Transform, Vec3},
+    ecs::event::ManualEventReader,
+    prelude::{error, warn, Entity, Events, Name, Transform, Vec3},
     time::Time,
     utils::Instant,
 };
 use bevy_rapier3d::prelude::RapierContext;
 use itertools::izip;
 
-use crate::lib::{tools::events::{ButtonState, CustomEvent}};
+use crate::lib::{tools::{events::{ButtonState, CustomEvent}, resources::Score, answer::{check_answer, AnswerCheck, AnswerFeedback, AnswerTolerance}}};
 
 use super::Action;
 
@@ -27,7 +28,11 @@ pub struct TestChamberAction {
     pub repeats_globally: bool,
     pub chosen_route_index: usize,
     pub name: String,
-    pub input_field: String
+    pub input_field: String,
+    pub tolerance: AnswerTolerance,
+    /// Sent along with the wrong path
+    pub feedback: AnswerFeedback,
+    answers: ManualEventReader<CustomEvent>,
 }
 
 impl Action for TestChamberAction {
@@ -39,7 +44,7 @@ impl Action for TestChamberAction {
     }
     fn try_startup(&mut self, me: bevy::prelude::Entity, world: &mut bevy::prelude::World) {
         if !self.is_started {
-            
+            self.answers = world.resource::<Events<CustomEvent>>().get_reader_current();
             self.is_started = true;
         }
     }
@@ -56,12 +61,15 @@ impl Action for TestChamberAction {
         let binding = main.get("#test_chamber_answer").unwrap().as_array().unwrap().iter().map(|v| v.as_str().unwrap().to_owned()).collect::<Vec<_>>();
         let a = binding;
 
-        if q.len() != w.len() || q.len() != r.len() || q.len() != a.len() {
-            panic!("action:test_chamber is all tangled up");
+        // extra entries of the longer arrays are left out
+        let len = q.len().min(w.len()).min(r.len()).min(a.len());
+        if len == 0 {
+            // an empty route list would be indexed once triggered, so it stays inert
+            error!("action:test_chamber has no complete route (#test_chamber_routes, wrongs, rights and answer), it does nothing");
+        } else if q.len() != len || w.len() != len || r.len() != len || a.len() != len {
+            warn!("action:test_chamber is all tangled up, routes, wrongs, rights and answers differ in length. questions.json with action:quiz is easier");
         }
 
-        let len = q.len();
-
         unsafe {        
             // i don't care about repeating for now
 
@@ -79,23 +87,35 @@ impl Action for TestChamberAction {
                 is_started: false,
                 is_triggered: false,
                 name: "test chamber".into(),
-                input_field: main.get("#test_chamber_input_name").unwrap().as_str().unwrap().to_owned()
+                input_field: main.get("#test_chamber_input_name").unwrap().as_str().unwrap().to_owned(),
+                tolerance: AnswerTolerance::from_extras(main),
+                feedback: AnswerFeedback::from_extras(main),
+                answers: Default::default(),
             }
         }
     }
 
     fn predicate(&mut self, world: &mut bevy::prelude::World) -> bool {
+        if self.routes.is_empty() {
+            return false;
+        }
         if self.is_triggered {
             let a = world.get_resource::<Events<CustomEvent>>().unwrap();
-            let mut b = a.get_reader();
-            if let Some(a) = b.read(a).find(|&p| p.name == self.input_field) {
+            // each answer is read once, otherwise it is scored twice
+            if let Some(a) = self.answers.read(a).find(|&p| p.name == self.input_field).map(|p| p.json_encoded.clone()) {
                 // std::fs::write("foo.txt",format!("INPUT: {} vs {}", a.json_encoded, self.routes[self.chosen_route_index].answer));
-                if a.json_encoded.replace(|c: char| !c.is_ascii(), "") == self.routes[self.chosen_route_index].answer {
+                let check = check_answer(&a, &self.routes[self.chosen_route_index].answer, self.tolerance);
+                if check == AnswerCheck::Right {
+                    world.resource_mut::<Score>().right += 1;
                     world.get_resource_mut::<Events<ButtonState>>()
                         .unwrap().send(ButtonState { is_pressed: true, just_changed: true, id: self.routes[self.chosen_route_index].right_path });
                 } else {
+                    world.resource_mut::<Score>().wrong += 1;
                     world.get_resource_mut::<Events<ButtonState>>()
                         .unwrap().send(ButtonState { is_pressed: true, just_changed: true, id: self.routes[self.chosen_route_index].wrong_path });
+                    if let Some(id) = self.feedback.signal(check) {
+                        world.send_event(ButtonState { is_pressed: true, just_changed: true, id });
+                    }
                 }
             }
         } else {
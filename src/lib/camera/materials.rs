@@ -1,5 +1,5 @@
 use bevy::{
-    prelude::{Component, Handle, Image, Asset},
+    prelude::{Color, Component, Handle, Image, Asset},
     reflect::{TypePath, TypeUuid},
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::Material2d,
@@ -56,8 +56,12 @@ pub struct ThirdPassMaterial {
     #[texture(0)]
     #[sampler(1)]
     pub(crate) source_image: Handle<Image>,
+    /// Outline thickness in pixels
     #[uniform(2)]
     pub(crate) redius: f32,
+    /// Alpha is used for fading in and out
+    #[uniform(3)]
+    pub(crate) color: Color,
 }
 
 impl Material2d for ThirdPassMaterial {
@@ -7,12 +7,14 @@ use crate::GameState;
 
 use self::{
     materials::{FirstPassMaterial, SecondPassMaterial, ThirdPassMaterial},
+    outline::drive_outline,
     setup::setup,
 };
 
 mod materials;
 // mod resize;
 mod components;
+pub mod outline;
 mod setup;
 
 pub struct GameCameraPlugin;
@@ -28,6 +30,6 @@ impl Plugin for GameCameraPlugin {
             Material2dPlugin::<ThirdPassMaterial>::default(),
         ))
         .insert_resource(Msaa::Off)
-        .add_systems(Update, setup.run_if(in_state(GameState::Game)));
+        .add_systems(Update, (setup, drive_outline).distributive_run_if(in_state(GameState::Game)));
     }
 }
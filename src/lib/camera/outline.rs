@@ -0,0 +1,116 @@
+use bevy::{
+    core::Name,
+    pbr::{NotShadowCaster, PbrBundle, StandardMaterial},
+    prelude::{
+        Assets, BuildChildren, Children, Commands, Component, DespawnRecursiveExt, Entity, Handle,
+        Mesh, Query, Res, ResMut, Resource, With, Without,
+    },
+    render::view::RenderLayers,
+    time::Time,
+};
+
+use crate::lib::interaction::{Interactable, InteractionFocus, OutlineStyle};
+
+use super::materials::ThirdPassMaterial;
+
+/// Only the outline mask camera renders this layer
+pub const OUTLINE_MASK_LAYER: u8 = 1;
+
+/// How much of the fade is done per second
+const OUTLINE_FADE_SPEED: f32 = 6.;
+
+/// Spawned together with the player camera, holds what is outlined right now
+#[derive(Resource)]
+pub struct OutlinePass {
+    pub material: Handle<ThirdPassMaterial>,
+    pub mask_material: Handle<StandardMaterial>,
+    /// Interactable whose meshes are in the mask
+    pub target: Option<Entity>,
+    pub style: OutlineStyle,
+    pub alpha: f32,
+}
+
+/// Unlit copy of an outlined mesh, seen only by the mask camera
+#[derive(Component)]
+pub struct OutlineMaskMarker;
+
+pub fn drive_outline(
+    mut commands: Commands,
+    outline_pass: Option<ResMut<OutlinePass>>,
+    focus: Res<InteractionFocus>,
+    interactable_q: Query<&Interactable>,
+    children_q: Query<&Children>,
+    mesh_q: Query<&Handle<Mesh>, Without<OutlineMaskMarker>>,
+    mask_q: Query<Entity, With<OutlineMaskMarker>>,
+    mut materials_a: ResMut<Assets<ThirdPassMaterial>>,
+    time: Res<Time>,
+) {
+    let Some(mut pass) = outline_pass else {
+        return;
+    };
+
+    let wanted = focus
+        .focused
+        .filter(|p| interactable_q.get(*p).is_ok_and(|p| p.outline.is_some()));
+
+    let step = time.delta_seconds() * OUTLINE_FADE_SPEED;
+
+    if pass.target == wanted {
+        if let Some(style) = wanted.and_then(|p| interactable_q.get(p).unwrap().outline) {
+            // actions may recolor it at any time, e.g. a door getting locked
+            pass.style = style;
+            pass.alpha = (pass.alpha + step).min(1.);
+        }
+    } else if pass.alpha > 0. && pass.target.is_some() {
+        // old one has to fade out before the mask is rebuilt
+        pass.alpha = (pass.alpha - step).max(0.);
+    } else {
+        for mask in mask_q.iter() {
+            commands.entity(mask).despawn_recursive();
+        }
+        pass.alpha = 0.;
+        pass.target = wanted;
+
+        if let Some(target) = wanted {
+            let interactable = interactable_q.get(target).unwrap();
+            pass.style = interactable.outline.unwrap();
+
+            let mut stack = vec![interactable.outline_target.unwrap_or(target)];
+            while let Some(entity) = stack.pop() {
+                if let Ok(mesh) = mesh_q.get(entity) {
+                    let mask_material = pass.mask_material.clone();
+                    commands.entity(entity).with_children(|p| {
+                        p.spawn((
+                            PbrBundle {
+                                mesh: mesh.clone(),
+                                material: mask_material,
+                                ..Default::default()
+                            },
+                            RenderLayers::layer(OUTLINE_MASK_LAYER),
+                            NotShadowCaster,
+                            OutlineMaskMarker,
+                            Name::new("Outline mask"),
+                        ));
+                    });
+                }
+                if let Ok(children) = children_q.get(entity) {
+                    stack.extend(children.iter());
+                }
+            }
+        }
+    }
+
+    let color = pass.style.color;
+    let color = color.with_a(color.a() * pass.alpha);
+    let thickness = pass.style.thickness;
+
+    // touching the asset re-uploads it, so only do that when something changed
+    if materials_a
+        .get(&pass.material)
+        .is_some_and(|p| p.color != color || p.redius != thickness)
+    {
+        let material = materials_a.get_mut(&pass.material).unwrap();
+        material.redius = thickness;
+        material.color = color;
+    }
+}
@@ -6,7 +6,7 @@ use bevy::core_pipeline::prepass::{DepthPrepass, NormalPrepass};
 use bevy::core_pipeline::tonemapping::{DebandDither, Tonemapping};
 use bevy::core_pipeline::Skybox;
 use bevy::pbr::ScreenSpaceAmbientOcclusionBundle;
-use bevy::prelude::{AmbientLight, UiCameraConfig, Res};
+use bevy::prelude::{AmbientLight, UiCameraConfig, Res, StandardMaterial};
 use bevy::{
     core_pipeline::clear_color::ClearColorConfig,
     prelude::{
@@ -26,10 +26,12 @@ use bevy::{
     window::Window,
 };
 
+use crate::lib::interaction::OutlineStyle;
 use crate::lib::tools::resources::AllSettings;
 use crate::lib::tools::{events, markers};
 
 use super::materials::{FirstPassMaterial, SecondPassMaterial, ThirdPassMaterial};
+use super::outline::{OutlinePass, OUTLINE_MASK_LAYER};
 
 pub fn setup(
     mut commands: Commands,
@@ -38,7 +40,7 @@ pub fn setup(
     mut first_pass_material_a: ResMut<Assets<FirstPassMaterial>>,
     mut second_pass_material_a: ResMut<Assets<SecondPassMaterial>>,
     mut third_pass_material_a: ResMut<Assets<ThirdPassMaterial>>,
-    // mut materials_a: ResMut<Assets<StandardMaterial>>,
+    mut materials_a: ResMut<Assets<StandardMaterial>>,
     mut images_a: ResMut<Assets<Image>>,
     player: Res<AllSettings>,
 
@@ -66,25 +68,13 @@ pub fn setup(
         ..Default::default()
     };
 
-    let image_handle = {
-        let mut image = Image {
-            texture_descriptor: TextureDescriptor {
-                label: None,
-                size,
-                dimension: TextureDimension::D2,
-                format: TextureFormat::Rgba32Float,
-                mip_level_count: 1,
-                sample_count: 1,
-                usage: TextureUsages::TEXTURE_BINDING
-                    | TextureUsages::COPY_DST
-                    | TextureUsages::RENDER_ATTACHMENT,
-                view_formats: &[],
-            },
-            ..Default::default()
-        };
-        image.resize(size);
-        images_a.add(image)
-    };
+    // mask of outlined objects, then jump flood passes bounce between the other two,
+    // as no pass can sample the texture it renders into
+    let mask_image_handle = images_a.add(outline_target_image(size));
+    let ping_pong_handles = [
+        images_a.add(outline_target_image(size)),
+        images_a.add(outline_target_image(size)),
+    ];
 
     let mut id = Entity::PLACEHOLDER;
 
@@ -141,6 +131,35 @@ pub fn setup(
                 ))
                 .id();
 
+            p.spawn((
+                markers::PlayerBorderPostProcessCamera,
+                UiCameraConfig { show_ui: false },
+                Camera3dBundle {
+                    camera: Camera {
+                        target: RenderTarget::Image(mask_image_handle.clone()),
+                        hdr: true,
+                        // renders before the main camera, so the mask is ready for post processing
+                        order: -1,
+                        ..Default::default()
+                    },
+                    projection: bevy::prelude::Projection::Perspective(
+                        bevy::prelude::PerspectiveProjection {
+                            fov: std::f32::consts::FRAC_PI_2/90.*player.fov,
+                            far: 20.,
+                            ..Default::default()
+                        },
+                    ),
+                    camera_3d: Camera3d {
+                        clear_color: ClearColorConfig::Custom(Color::BLACK),
+                        ..Default::default()
+                    },
+                    tonemapping: Tonemapping::None,
+                    ..Default::default()
+                },
+                RenderLayers::layer(OUTLINE_MASK_LAYER),
+                Name::new("Outline mask camera"),
+            ));
+
             // p.spawn((
             //     markers::PlayerCamera,
             //     markers::PlayerBorderPostProcessCamera,
@@ -207,7 +226,6 @@ pub fn setup(
         }
     }
 
-    return;
     {
         // First pass
         let quad_handle = meshes_a.add(Mesh::from(Quad::new(Vec2::new(
@@ -216,7 +234,7 @@ pub fn setup(
         ))));
 
         let first_pass_material_handle = first_pass_material_a.add(FirstPassMaterial {
-            source_image: image_handle.clone(),
+            source_image: mask_image_handle.clone(),
         });
 
         // Post processing 2d quad, with material using the render texture done by the main camera, with a custom shader.
@@ -239,7 +257,7 @@ pub fn setup(
         commands.spawn((
             Camera2dBundle {
                 camera: Camera {
-                    target: RenderTarget::Image(image_handle.clone()),
+                    target: RenderTarget::Image(ping_pong_handles[0].clone()),
                     hdr: true,
                     // renders after the first main camera which has default value: 0.
                     order: 1,
@@ -247,13 +265,14 @@ pub fn setup(
                 },
                 ..Default::default()
             },
+            UiCameraConfig { show_ui: false },
             RenderLayers::layer(3),
             markers::PlayerBorderPostProcessCamera,
             Name::new("Post process camera #1"),
         ));
     }
 
-    const LEN: u8 = 5;
+    const LEN: u8 = 4;
 
     for i in 1..=LEN {
         // Second pass (passes)
@@ -264,7 +283,7 @@ pub fn setup(
 
         // This material has the texture that has been rendered.
         let second_material_handle = second_pass_material_a.add(SecondPassMaterial {
-            source_image: image_handle.clone(),
+            source_image: ping_pong_handles[(i as usize - 1) % 2].clone(),
             intensity: (16 >> i) as f32,
         });
 
@@ -289,13 +308,14 @@ pub fn setup(
             Camera2dBundle {
                 camera: Camera {
                     // renders after the first main camera which has default value: 0.
-                    target: RenderTarget::Image(image_handle.clone()),
+                    target: RenderTarget::Image(ping_pong_handles[i as usize % 2].clone()),
                     order: 1 + i as isize,
                     hdr: true,
                     ..Default::default()
                 },
                 ..Default::default()
             },
+            UiCameraConfig { show_ui: false },
             RenderLayers::layer(3 + i),
             markers::PlayerBorderPostProcessCamera,
             Name::new("Post process camera #2"),
@@ -311,8 +331,21 @@ pub fn setup(
 
         // This material has the texture that has been rendered.
         let material_handle = third_pass_material_a.add(ThirdPassMaterial {
-            source_image: image_handle.clone(),
-            redius: 6.0,
+            source_image: ping_pong_handles[LEN as usize % 2].clone(),
+            redius: 0.0,
+            color: Color::NONE,
+        });
+
+        commands.insert_resource(OutlinePass {
+            material: material_handle.clone(),
+            mask_material: materials_a.add(StandardMaterial {
+                base_color: Color::WHITE,
+                unlit: true,
+                ..Default::default()
+            }),
+            target: None,
+            style: OutlineStyle::default(),
+            alpha: 0.,
         });
 
         // Post processing 2d quad, with material using the render texture done by the main camera, with a custom shader.
@@ -345,6 +378,7 @@ pub fn setup(
                 },
                 ..Default::default()
             },
+            UiCameraConfig { show_ui: false },
             RenderLayers::layer(3 + LEN + 1),
             markers::PlayerBorderPostProcessCamera,
             Name::new("Post process camera #3"),
@@ -354,6 +388,26 @@ pub fn setup(
     camera_ev_r.clear();
 }
 
+fn outline_target_image(size: Extent3d) -> Image {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba32Float,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..Default::default()
+    };
+    image.resize(size);
+    image
+}
+
 // pub fn add_camera(
 //     mut commands: Commands,
 //     mut event_r: EventReader<events::camera::PlayerCameraAddEvent>,
@@ -3,13 +3,13 @@ use std::sync::Arc;
 use bevy::{
     prelude::{
         BuildChildren, Color, Commands, Component, EventReader, NodeBundle, Plugin, Query,
-        TextBundle, Update, Visibility, With,
+        Res, TextBundle, Update, Visibility, With,
     },
     text::Text,
     transform::commands,
 };
 
-use super::tools::events::ProposePopup;
+use super::tools::{events::ProposePopup, resources::Locale};
 
 pub struct HintOverlayPlugin;
 
@@ -26,6 +26,7 @@ fn show_hint(
     mut commands: Commands,
     mut ev_r: EventReader<ProposePopup>,
     mut query: Query<(&mut Text, &mut Visibility), With<HintTextUI>>,
+    locale: Res<Locale>,
 ) {
     let mut max: Option<(u32, usize)> = None;
     if ev_r.is_empty() {
@@ -43,6 +44,7 @@ fn show_hint(
         }
         evs[max.unwrap().1]
     };
+    let text = locale.resolve(&ev.text);
     if query.is_empty() {
         commands
             .spawn(NodeBundle {
@@ -58,13 +60,13 @@ fn show_hint(
             .with_children(|p| {
                 p.spawn((
                     HintTextUI,
-                    TextBundle::from_section(ev.text.as_str(), ev.style.clone()),
+                    TextBundle::from_section(text, ev.style.clone()),
                 ));
             });
     } else {
         *query.single_mut().1 = Visibility::Visible;
-        if query.single_mut().0.sections[0].value != *ev.text {
-            query.single_mut().0.sections[0].value = (*ev.text).clone();
+        if query.single_mut().0.sections[0].value != text {
+            query.single_mut().0.sections[0].value = text;
             query.single_mut().0.sections[0].style = ev.style.clone();
             println!("{:?}", ev.style);
         }
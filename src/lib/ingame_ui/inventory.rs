@@ -0,0 +1,102 @@
+use bevy::{
+    prelude::{
+        AssetServer, BuildChildren, Color, Commands, Component, DespawnRecursiveExt, Entity,
+        ImageBundle, NodeBundle, Query, Ref, Res, ResMut, TextBundle, With,
+    },
+    text::TextStyle,
+    ui::{AlignItems, BackgroundColor, FlexDirection, PositionType, Style, UiImage, UiRect, Val},
+};
+
+use crate::lib::tools::{
+    consts::{font_names, styles},
+    resources::{Inventory, Locale},
+};
+
+#[derive(Component)]
+pub struct InventoryHudMarker;
+
+pub fn spawn_inventory_hud(mut commands: Commands) {
+    commands.spawn((
+        InventoryHudMarker,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.),
+                right: Val::Px(20.),
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ));
+}
+
+pub fn update_inventory_hud(
+    mut commands: Commands,
+    inventory: Res<Inventory>,
+    hud_q: Query<(Entity, Ref<InventoryHudMarker>)>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    let Ok((hud, marker)) = hud_q.get_single() else {
+        return;
+    };
+    if !inventory.is_changed() && !marker.is_added() && !locale.is_changed() {
+        return;
+    }
+
+    let text_style = TextStyle {
+        font: asset_server.load(font_names::NOTO_SANS_SM_BOLD),
+        font_size: 20.,
+        color: Color::BLACK,
+    };
+
+    commands.entity(hud).despawn_descendants();
+    commands.entity(hud).with_children(|parent| {
+        for item in inventory.items.iter() {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(6.)),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(styles::button::LESS_TRANSPARENT_WHITE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    if let Some(icon) = &item.icon {
+                        parent.spawn(ImageBundle {
+                            image: UiImage::new(asset_server.load(icon.clone())),
+                            style: Style {
+                                width: Val::Px(64.),
+                                height: Val::Px(64.),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        });
+                    }
+                    let name = locale.resolve(&item.name);
+                    let label = if item.count > 1 {
+                        format!("{} x{}", name, item.count)
+                    } else {
+                        name
+                    };
+                    parent.spawn(TextBundle::from_section(label, text_style.clone()));
+                });
+        }
+    });
+}
+
+pub fn despawn_inventory_hud(
+    mut commands: Commands,
+    mut inventory: ResMut<Inventory>,
+    hud_q: Query<Entity, With<InventoryHudMarker>>,
+) {
+    for hud in hud_q.iter() {
+        commands.entity(hud).despawn_recursive();
+    }
+    inventory.items.clear();
+}
@@ -1,9 +1,11 @@
+pub mod inventory;
 pub mod systems;
 
-use bevy::prelude::{in_state, IntoSystemConfigs, OnEnter, Plugin, Update};
+use bevy::prelude::{in_state, IntoSystemConfigs, OnEnter, OnExit, Plugin, Update};
 
 use crate::GameState;
 
+use self::inventory::{despawn_inventory_hud, spawn_inventory_hud, update_inventory_hud};
 use self::systems::{prepare_cursor, unlock_cursor};
 
 pub struct InGameUiPlugin;
@@ -13,7 +15,11 @@ impl Plugin for InGameUiPlugin {
         "Plugin for handling in-game ui"
     }
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(OnEnter(GameState::Game), prepare_cursor)
-            .add_systems(Update, unlock_cursor.run_if(in_state(GameState::Game)));
+        app.add_systems(OnEnter(GameState::Game), (prepare_cursor, spawn_inventory_hud))
+            .add_systems(OnExit(GameState::Game), despawn_inventory_hud)
+            .add_systems(
+                Update,
+                (unlock_cursor, update_inventory_hud).distributive_run_if(in_state(GameState::Game)),
+            );
     }
 }
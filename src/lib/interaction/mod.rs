@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use bevy::{
+    input::InputSystem,
+    prelude::{
+        in_state, AssetServer, Color, Commands, Component, DespawnRecursiveExt, Entity,
+        EventWriter, GlobalTransform, Input, IntoSystemConfigs, KeyCode, NodeBundle, OnEnter,
+        OnExit, Plugin, PreUpdate, Query, Res, ResMut, Resource, State, Update, With,
+    },
+    text::TextStyle,
+    ui::{BackgroundColor, PositionType, Style, Val},
+};
+use bevy_rapier3d::prelude::{QueryFilter, RapierContext, Sensor};
+use serde_json::Value;
+
+use crate::{GameState, PlayerState};
+
+use super::{
+    broadcast,
+    tools::{
+        consts::font_names,
+        events::ProposePopup,
+        markers::{PlayerCamera, PlayerParentMarker},
+    },
+};
+
+/// Key that activates whatever the player is looking at
+pub const INTERACTION_KEY: KeyCode = KeyCode::E;
+
+/// Nothing further than this is even considered, whatever `Interactable::range` says
+pub const MAX_INTERACTION_RANGE: f32 = 10.;
+
+const CROSSHAIR_SIZE: f32 = 4.;
+const CROSSHAIR_FOCUSED_SIZE: f32 = 10.;
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn name(&self) -> &str {
+        "Plugin for picking the object player is looking at"
+    }
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<InteractionFocus>()
+            .add_systems(OnEnter(GameState::Game), spawn_crosshair)
+            .add_systems(OnExit(GameState::Game), despawn_crosshair)
+            .add_systems(
+                PreUpdate,
+                focus_interactable
+                    .after(InputSystem)
+                    .before(broadcast::run_all)
+                    .run_if(in_state(GameState::Game)),
+            )
+            .add_systems(Update, update_crosshair.run_if(in_state(GameState::Game)));
+    }
+}
+
+/// Put it on a collider to make it a candidate for focusing.
+/// Actions insert it on startup instead of raycasting on their own
+#[derive(Component, Clone)]
+pub struct Interactable {
+    pub range: f32,
+    /// Of all interactables on the ray the one with the highest priority is focused, the closer one on a tie.
+    /// Also the priority of the hint popup
+    pub priority: u32,
+    pub hint: Arc<String>,
+    /// Disabled interactables are ignored, e.g. a button on cooldown
+    pub enabled: bool,
+    /// `None` means the object is not outlined while focused
+    pub outline: Option<OutlineStyle>,
+    /// Whose meshes get outlined, if not the collider itself (a door and not its usage area)
+    pub outline_target: Option<Entity>,
+}
+
+impl Interactable {
+    pub fn new(range: f32, hint: Arc<String>) -> Interactable {
+        Interactable {
+            range,
+            priority: 1,
+            hint,
+            enabled: true,
+            outline: Some(OutlineStyle::default()),
+            outline_target: None,
+        }
+    }
+
+    /// Reads `#interaction_range` and outline settings from extras
+    pub fn from_extras(
+        main: &serde_json::map::Map<String, Value>,
+        default_range: f32,
+        hint: Arc<String>,
+    ) -> Interactable {
+        let mut a = Interactable::new(range_from_extras(main, default_range), hint);
+        a.outline = OutlineStyle::from_extras(main);
+        a
+    }
+
+    pub fn with_priority(mut self: Self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_outline_target(mut self: Self, target: Entity) -> Self {
+        self.outline_target = Some(target);
+        self
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct OutlineStyle {
+    pub color: Color,
+    /// In pixels, up to 15
+    pub thickness: f32,
+}
+
+impl Default for OutlineStyle {
+    fn default() -> Self {
+        OutlineStyle {
+            color: Color::rgba(0.3, 1.0, 0.3, 1.0),
+            thickness: 3.,
+        }
+    }
+}
+
+impl OutlineStyle {
+    /// `#outline = false` turns it off, `#outline_color = [f32;4]`, `#outline_thickness = f32`
+    pub fn from_extras(main: &serde_json::map::Map<String, Value>) -> Option<OutlineStyle> {
+        if main.get("#outline").and_then(|p| p.as_bool()) == Some(false) {
+            return None;
+        }
+        let mut a = OutlineStyle::default();
+        if let Some(color) = color_from_extras(main, "#outline_color") {
+            a.color = color;
+        }
+        if let Some(thickness) = main.get("#outline_thickness").and_then(|p| p.as_f64()) {
+            a.thickness = thickness as f32;
+        }
+        Some(a)
+    }
+}
+
+/// Same `[r, g, b, a]` layout as `ambient_color`
+pub fn color_from_extras(main: &serde_json::map::Map<String, Value>, name: &str) -> Option<Color> {
+    main.get(name)
+        .and_then(|p| p.as_array())
+        .and_then(|p| {
+            p.get(0..4).and_then(|x| {
+                x.iter()
+                    .map(|p| p.as_f64().and_then(|p| Some(p as f32)))
+                    .collect::<Option<Vec<f32>>>()
+            })
+        })
+        .map(|v| Color::rgba(v[0], v[1], v[2], v[3]))
+}
+
+/// Single source of truth about what the player is looking at this frame
+#[derive(Resource, Default)]
+pub struct InteractionFocus {
+    pub focused: Option<Entity>,
+    /// `true` only on the frame `INTERACTION_KEY` was pressed while something was focused
+    pub activated: bool,
+    pub distance: f32,
+}
+
+impl InteractionFocus {
+    pub fn is_focused(&self, entity: Entity) -> bool {
+        self.focused == Some(entity)
+    }
+
+    pub fn is_activated(&self, entity: Entity) -> bool {
+        self.activated && self.focused == Some(entity)
+    }
+}
+
+/// Reads `#interaction_range` from extras, falling back to action's own default
+pub fn range_from_extras(main: &serde_json::map::Map<String, Value>, default: f32) -> f32 {
+    main.get("#interaction_range")
+        .and_then(|p| p.as_f64())
+        .map(|p| p as f32)
+        .unwrap_or(default)
+}
+
+#[derive(Component)]
+struct CrosshairUI;
+
+pub fn focus_interactable(
+    camera_q: Query<&GlobalTransform, With<PlayerCamera>>,
+    interactable_q: Query<&Interactable>,
+    sensor_q: Query<(), With<Sensor>>,
+    player_q: Query<(), With<PlayerParentMarker>>,
+    rapier_context: Option<Res<RapierContext>>,
+    keys: Res<Input<KeyCode>>,
+    state: Res<State<PlayerState>>,
+    asset_server: Res<AssetServer>,
+    mut focus: ResMut<InteractionFocus>,
+    mut popup_ev_w: EventWriter<ProposePopup>,
+) {
+    focus.focused = None;
+    focus.activated = false;
+
+    if *state != PlayerState::Interactive {
+        return;
+    }
+
+    let Some(camera) = camera_q.iter().next() else {
+        return;
+    };
+    let Some(ctx) = rapier_context else {
+        return;
+    };
+
+    let origin = camera.translation();
+    let direction = camera.forward();
+
+    // walls and other solid geometry block the ray, sensors do not
+    let blocker = ctx
+        .cast_ray(
+            origin,
+            direction,
+            MAX_INTERACTION_RANGE,
+            true,
+            QueryFilter {
+                predicate: Some(&|entity| !player_q.contains(entity) && !sensor_q.contains(entity)),
+                ..Default::default()
+            },
+        )
+        .map_or(MAX_INTERACTION_RANGE, |p| p.1);
+
+    // every interactable up to the blocker, which may be a solid interactable itself
+    let mut best: Option<(Entity, f32, u32)> = None;
+    ctx.intersections_with_ray(
+        origin,
+        direction,
+        blocker + 0.001,
+        true,
+        QueryFilter {
+            predicate: Some(&|entity| !player_q.contains(entity) && interactable_q.contains(entity)),
+            ..Default::default()
+        },
+        |entity, hit| {
+            let Ok(p) = interactable_q.get(entity) else {
+                return true;
+            };
+            let better = best.map_or(true, |(_, toi, priority)| {
+                p.priority > priority || (p.priority == priority && hit.toi < toi)
+            });
+            if p.enabled && hit.toi <= p.range && better {
+                best = Some((entity, hit.toi, p.priority));
+            }
+            true
+        },
+    );
+    let Some((entity, toi, priority)) = best else {
+        return;
+    };
+
+    focus.focused = Some(entity);
+    focus.distance = toi;
+    focus.activated = keys.just_pressed(INTERACTION_KEY);
+
+    let hint = &interactable_q.get(entity).unwrap().hint;
+    if !hint.is_empty() {
+        popup_ev_w.send(ProposePopup {
+            text: hint.clone(),
+            priority,
+            style: TextStyle {
+                font: asset_server.load(font_names::NOTO_SANS_MEDIUM),
+                font_size: 32.0,
+                color: Color::WHITE,
+            },
+            key: Some(INTERACTION_KEY),
+        });
+    }
+}
+
+fn spawn_crosshair(mut commands: Commands) {
+    commands.spawn((
+        CrosshairUI,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(CROSSHAIR_SIZE),
+                height: Val::Px(CROSSHAIR_SIZE),
+                left: Val::Percent(50.),
+                top: Val::Percent(50.),
+                margin: bevy::ui::UiRect::all(Val::Px(-CROSSHAIR_SIZE / 2.)),
+                ..Default::default()
+            },
+            background_color: BackgroundColor(Color::rgba(1., 1., 1., 0.6)),
+            ..Default::default()
+        },
+    ));
+}
+
+fn update_crosshair(
+    focus: Res<InteractionFocus>,
+    state: Res<State<PlayerState>>,
+    mut crosshair_q: Query<(&mut Style, &mut BackgroundColor), With<CrosshairUI>>,
+) {
+    let Ok((mut style, mut color)) = crosshair_q.get_single_mut() else {
+        return;
+    };
+
+    let size = if focus.focused.is_some() {
+        color.0 = Color::rgba(1., 1., 1., 0.95);
+        CROSSHAIR_FOCUSED_SIZE
+    } else if *state == PlayerState::Interactive {
+        color.0 = Color::rgba(1., 1., 1., 0.6);
+        CROSSHAIR_SIZE
+    } else {
+        color.0 = Color::NONE;
+        CROSSHAIR_SIZE
+    };
+
+    if style.width != Val::Px(size) {
+        style.width = Val::Px(size);
+        style.height = Val::Px(size);
+        style.margin = bevy::ui::UiRect::all(Val::Px(-size / 2.));
+    }
+}
+
+fn despawn_crosshair(
+    mut commands: Commands,
+    mut focus: ResMut<InteractionFocus>,
+    crosshair_q: Query<Entity, With<CrosshairUI>>,
+) {
+    for entity in crosshair_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *focus = InteractionFocus::default();
+}
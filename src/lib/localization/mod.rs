@@ -0,0 +1,121 @@
+use bevy::{
+    prelude::{Component, IntoSystemConfigs, Plugin, Query, Ref, Res, ResMut, Update, Window},
+    text::Text,
+    utils::HashMap,
+};
+
+use super::tools::{
+    config, level_pack,
+    resources::{AllSettings, LoadingSceneInfo, Locale},
+};
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn name(&self) -> &str {
+        "For switching languages of the ui and levels"
+    }
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            (load_locale, apply_localized_text, update_window_title).chain(),
+        );
+    }
+}
+
+/// Text sections filled through `Locale::resolve`, one entry per section.
+/// Refilled whenever the language changes
+#[derive(Component)]
+pub struct LocalizedText(pub Vec<String>);
+
+impl LocalizedText {
+    pub fn new(text: &str) -> LocalizedText {
+        LocalizedText(vec![text.to_owned()])
+    }
+}
+
+fn parse_table(bytes: &[u8], name: &str) -> HashMap<String, String> {
+    match serde_json::from_slice::<HashMap<String, String>>(bytes) {
+        Ok(p) => p,
+        Err(err) => {
+            println!("{} is not {{ \"key\": \"text\" }}: {}", name, err);
+            HashMap::default()
+        }
+    }
+}
+
+/// Reads the tables again once the language or the level changes
+pub fn load_locale(
+    settings: Res<AllSettings>,
+    scene_info: Option<Res<LoadingSceneInfo>>,
+    mut locale: ResMut<Locale>,
+) {
+    let level = scene_info.map(|p| p.name.clone()).unwrap_or_default();
+    let language_changed = locale.language != settings.language;
+    if !language_changed && locale.level_name == level {
+        return;
+    }
+
+    if language_changed {
+        let path = config::assets_path()
+            .join("lang")
+            .join(format!("{}.json", settings.language));
+        locale.ui = match std::fs::read(&path) {
+            Ok(bytes) => parse_table(&bytes, &path.to_string_lossy()),
+            Err(_) => {
+                println!("No string table {}", path.display());
+                HashMap::default()
+            }
+        };
+        locale.language = settings.language.clone();
+    }
+
+    let file = format!("lang/{}.json", settings.language);
+    locale.level = if level.is_empty() {
+        HashMap::default()
+    } else {
+        level_pack::read_level_file(&level, &file)
+            .map(|p| parse_table(&p, &file))
+            .unwrap_or_default()
+    };
+    locale.level_name = level;
+}
+
+pub fn apply_localized_text(
+    locale: Res<Locale>,
+    mut text_q: Query<(Ref<LocalizedText>, &mut Text)>,
+) {
+    for (localized, mut text) in text_q.iter_mut() {
+        if !locale.is_changed() && !localized.is_changed() {
+            continue;
+        }
+        for (section, entry) in text.sections.iter_mut().zip(localized.0.iter()) {
+            section.value = locale.resolve(entry);
+        }
+    }
+}
+
+/// "window.title" in the language of the saved settings, for the window made before any system runs
+pub fn initial_window_title() -> String {
+    let language = std::fs::read(config::assets_path().join("data/settings.json"))
+        .ok()
+        .and_then(|p| serde_json::from_slice::<AllSettings>(&p).ok())
+        .map(|p| p.language)
+        .unwrap_or_else(|| "uk".into());
+    let path = config::assets_path().join("lang").join(format!("{}.json", language));
+    std::fs::read(&path)
+        .ok()
+        .and_then(|p| parse_table(&p, &path.to_string_lossy()).remove("window.title"))
+        .unwrap_or_default()
+}
+
+pub fn update_window_title(locale: Res<Locale>, mut windows: Query<&mut Window>) {
+    if !locale.is_changed() {
+        return;
+    }
+    if let Some(title) = locale.get("window.title") {
+        for mut window in windows.iter_mut() {
+            window.title = title.to_owned();
+        }
+    }
+}
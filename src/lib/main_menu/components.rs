@@ -1,6 +1,8 @@
 use std::ffi::OsString;
 
-use bevy::prelude::{Component, Color};
+use bevy::prelude::{Component, Color, Handle, Image};
+
+use crate::lib::tools::config::LevelSchema;
 
 
 
@@ -11,6 +13,23 @@ pub struct MainMenuMarker;
 #[derive(Component)]
 pub struct Level(pub OsString);
 
+/// Level button whose prerequisites are not completed yet, or which is of an unsupported version
+#[derive(Component)]
+pub struct LockedLevel;
+
+/// What the detail panel shows while the level button is hovered
+#[derive(Component)]
+pub struct LevelDetails {
+    pub config: LevelSchema,
+    pub image: Handle<Image>,
+}
+
+#[derive(Component)]
+pub struct LevelDetailsText;
+
+#[derive(Component)]
+pub struct LevelDetailsImage;
+
 #[derive(Component)]
 pub struct ButtonColors(pub Color,pub Color, pub Color);
 
@@ -46,6 +65,8 @@ pub enum SettingsButtonEnum {
     VolumeUp(u64),
     VolumeDown(u64),
     FovChange(i32),
+    /// Switches between uk and en
+    Language,
 }
 
 #[derive(Component)]
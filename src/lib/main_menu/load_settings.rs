@@ -3,7 +3,7 @@ use std::{env, path::PathBuf, fs, io::BufReader};
 use bevy::prelude::{ResMut};
 
 
-use crate::lib::tools::resources::AllSettings;
+use crate::lib::tools::{config, resources::{AllSettings, Progress}};
 
 pub fn load_settings(mut res: ResMut<AllSettings>) {
     let path = if let Ok(manifest_dir) = env::var("BEVY_ASSET_ROOT") {
@@ -45,4 +45,32 @@ pub fn load_settings(mut res: ResMut<AllSettings>) {
             *res = de;
         }
     }
-}
\ No newline at end of file
+}
+
+/// Reads `progress.json` once, after that it is only written
+pub fn load_progress(mut res: ResMut<Progress>) {
+    let path = config::assets_path().join("data/progress.json");
+    let Ok(file) = fs::File::open(path) else {
+        return;
+    };
+    match serde_json::from_reader::<_, Progress>(BufReader::new(file)) {
+        Ok(de) => *res = de,
+        Err(err) => println!("progress.json is broken, starting anew: {}", err),
+    }
+}
+
+pub fn save_progress(progress: &Progress) {
+    let path = config::assets_path().join("data");
+    if let Err(err) = fs::create_dir_all(path.clone()) {
+        println!("{}", err);
+        return;
+    }
+    let res = fs::write(
+        path.join("progress.json"),
+        serde_json::to_string(progress).expect("couldn't serialize progress into progress.json"),
+    );
+    match res {
+        Ok(_) => {}
+        Err(err) => println!("{}", err),
+    }
+}
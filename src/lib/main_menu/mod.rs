@@ -1,9 +1,9 @@
-use bevy::prelude::{in_state, IntoSystemConfigs, OnEnter, OnExit, Plugin, Update};
+use bevy::prelude::{in_state, IntoSystemConfigs, OnEnter, OnExit, Plugin, Startup, Update};
 
-use crate::GameState;
+use crate::{lib::localization::load_locale, GameState};
 
-use self::ui::{button_interactivity,level_interactivity, destroy_main_menu, prepare_main_menu, fix_images};
-use self::load_settings::load_settings;
+use self::ui::{button_interactivity,level_interactivity, level_details, destroy_main_menu, prepare_main_menu, fix_images};
+use self::load_settings::{load_progress, load_settings};
 
 pub mod components;
 pub mod ui;
@@ -15,10 +15,11 @@ impl Plugin for MainMenuPlugin {
         "For handling main menu"
     }
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(OnEnter(GameState::MainMenu), (load_settings,prepare_main_menu).chain())
+        app.add_systems(Startup, load_progress)
+            .add_systems(OnEnter(GameState::MainMenu), (load_settings,load_locale,prepare_main_menu).chain())
             .add_systems(
                 Update,
-                (fix_images, button_interactivity,level_interactivity).distributive_run_if(in_state(GameState::MainMenu)),
+                (fix_images, button_interactivity,level_interactivity, level_details).distributive_run_if(in_state(GameState::MainMenu)),
             )
             .add_systems(OnExit(GameState::MainMenu), destroy_main_menu);
     }
@@ -1,12 +1,12 @@
-use std::{time::Duration, fs::{File, self}, io::BufReader, path::PathBuf, env};
+use std::{time::Duration, fs, path::PathBuf, env};
 
 use bevy::{
     prelude::{
         AssetServer, BuildChildren, Button, ButtonBundle, Camera2dBundle, Changed, Color, Commands,
-        Component, DespawnRecursiveExt, Entity, ImageBundle, NodeBundle, Query, Res,
+        Component, DespawnRecursiveExt, Entity, Handle, Image, ImageBundle, NodeBundle, Query, Res,
         ResMut, State, TextBundle, With, Without, EventWriter, EventReader
     },
-    text::{ TextStyle, TextSection, BreakLineOn},
+    text::{ Text, TextStyle, TextSection, BreakLineOn},
     time::Time,
     ui::{
         AlignItems, BackgroundColor, FlexWrap, FlexDirection, Interaction, JustifyContent, PositionType,
@@ -18,12 +18,13 @@ use bevy_kira_audio::{Audio, AudioControl};
 
 
 use crate::{
-    lib::tools::{consts::{styles, font_names, self}, transition::TransitionMarker, resources::{MainMenuResource, AllSettings}, events::LoadLevel, config::LevelSchema, self},
+    lib::localization::LocalizedText,
+    lib::tools::{consts::{styles, font_names, self}, transition::TransitionMarker, resources::{MainMenuResource, AllSettings, Progress, Locale}, events::LoadLevel, config::{LevelSchema, self}, level_pack, self},
     GameState, 
 };
 
 use super::components::{
-    MainMenuButtonEnum, MainMenuButtonMarker, MainMenuMarker, RootNode, MainMenuVariants, Level, SettingsButtonMarker, SettingsButtonEnum, SettingsLabel, ButtonColors, QuickFixImageComponentMarker
+    MainMenuButtonEnum, MainMenuButtonMarker, MainMenuMarker, RootNode, MainMenuVariants, Level, LockedLevel, LevelDetails, LevelDetailsText, LevelDetailsImage, SettingsButtonMarker, SettingsButtonEnum, SettingsLabel, ButtonColors, QuickFixImageComponentMarker
 };
 
 pub fn button_interactivity(
@@ -140,6 +141,11 @@ pub fn button_interactivity(
                         }
                     }
                 }
+                (SettingsButtonEnum::Language, Interaction::Pressed) => {
+                    // the label follows by itself through LocalizedText
+                    player.language = if player.language == "uk" { "en".into() } else { "uk".into() };
+                }
+                (SettingsButtonEnum::Language, _) => {},
                 (SettingsButtonEnum::VolumeUp(_), _) => {},
                 (SettingsButtonEnum::VolumeDown(_), _) => {},
                 (SettingsButtonEnum::FovChange(_), _) => {},
@@ -185,7 +191,7 @@ pub fn level_interactivity(
     // mut roots: Query<&mut Style, With<RootNode>>,
     mut button_interaction: Query<
         (&Interaction, &mut BackgroundColor, &Level),
-        (Changed<Interaction>, With<Button>),
+        (Changed<Interaction>, With<Button>, Without<LockedLevel>),
     >,
     // state: Res<State<GameState>>,
     // mut next_state: ResMut<NextState<GameState>>,
@@ -213,7 +219,150 @@ pub fn level_interactivity(
 #[derive(Component)]
 pub struct MainMenuImageMarker;
 
-pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<AllSettings>) {
+/// Shows the last hovered level in the detail panel
+pub fn level_details(
+    hovered: Query<(&Interaction, &LevelDetails), Changed<Interaction>>,
+    mut text_q: Query<(&mut Text, &mut LocalizedText), With<LevelDetailsText>>,
+    mut image_q: Query<(&mut UiImage, &mut Style), With<LevelDetailsImage>>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    for (interaction, details) in hovered.iter() {
+        if *interaction == Interaction::None {
+            continue;
+        }
+        for (mut text, mut localized) in text_q.iter_mut() {
+            let sections = level_details_sections(&details.config, &asset_server);
+            localized.0 = sections.iter().map(|p| p.value.clone()).collect();
+            text.sections = sections
+                .into_iter()
+                .map(|p| TextSection::new(locale.resolve(&p.value), p.style))
+                .collect();
+        }
+        for (mut image, mut style) in image_q.iter_mut() {
+            image.texture = details.image.clone();
+            style.display = bevy::ui::Display::Flex;
+        }
+    }
+}
+
+/// Sections keep `@key` texts, they are resolved by the caller
+fn level_details_sections(config: &LevelSchema, asset_server: &AssetServer) -> Vec<TextSection> {
+    let heading = TextStyle {
+        font_size: 34.,
+        color: Color::BLACK,
+        font: asset_server.load(font_names::NOTO_SANS_BOLD),
+    };
+    let label = TextStyle {
+        font_size: 24.,
+        color: Color::DARK_GRAY,
+        font: asset_server.load(font_names::NOTO_SANS_SM_BOLD),
+    };
+    let body = TextStyle {
+        font_size: 24.,
+        color: Color::BLACK,
+        font: asset_server.load(font_names::NOTO_SANS_MEDIUM),
+    };
+
+    let mut sections = vec![TextSection::new(format!("{}\n", config.name), heading)];
+    if let Some(problem) = config.version_problem() {
+        sections.push(TextSection::new(problem, TextStyle { color: Color::RED, ..body.clone() }));
+        sections.push(TextSection::new("\n", body.clone()));
+    }
+    if let Some(description) = &config.description {
+        sections.push(TextSection::new(format!("\n{}\n\n", description), body.clone()));
+    }
+
+    let mut field = |name: &str, value: String| {
+        sections.push(TextSection::new(name, label.clone()));
+        sections.push(TextSection::new(": ", label.clone()));
+        sections.push(TextSection::new(value, body.clone()));
+        sections.push(TextSection::new("\n", body.clone()));
+    };
+    if let Some(author) = &config.author {
+        field("@level.author", author.clone());
+    }
+    if let Some(duration) = config.duration {
+        field("@level.duration", format!("@level.duration_value:{}", duration));
+    }
+    if let Some(difficulty) = config.difficulty {
+        field("@level.difficulty", format!("{}/5", difficulty.clamp(1, 5)));
+    }
+    if let Some(language) = &config.language {
+        field("@level.language", language.clone());
+    }
+    if !config.tags.is_empty() {
+        field("@level.tags", config.tags.join(", "));
+    }
+    if !config.objectives.is_empty() {
+        field(
+            "@level.objectives",
+            config
+                .objectives
+                .iter()
+                .map(|p| format!("\n - {}", p))
+                .collect::<String>(),
+        );
+    }
+    sections
+}
+
+/// "Пройдено · 3:25 · 5/6", what is left to do for a locked one, or nothing for a fresh level.
+/// One `LocalizedText` entry per part
+fn level_badge(
+    folder: &str,
+    levels: &[(String, LevelSchema)],
+    progress: &Progress,
+    locked: bool,
+) -> Option<(Vec<String>, Color)> {
+    if locked {
+        let completed = progress.completed();
+        let missing = levels
+            .iter()
+            .find(|p| p.0 == folder)
+            .map(|p| {
+                p.1.requires
+                    .iter()
+                    .filter(|p| !completed.contains(*p))
+                    .map(|p| {
+                        levels
+                            .iter()
+                            .find(|l| l.0 == *p)
+                            .map(|l| l.1.name.clone())
+                            .unwrap_or(p.clone())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        return Some((
+            vec![if missing.is_empty() {
+                "@badge.locked".into()
+            } else {
+                format!("@badge.locked_requires:{}", missing)
+            }],
+            Color::DARK_GRAY,
+        ));
+    }
+
+    let level = progress.levels.get(folder)?;
+    if !level.completed {
+        return level
+            .checkpoint
+            .map(|_| (vec!["@badge.started".to_owned()], Color::rgb(0.8, 0.5, 0.)));
+    }
+    let mut text = vec!["@badge.completed".to_owned()];
+    if let Some(time) = level.best_time {
+        let secs = time as u32;
+        text.push(format!(" · {}:{:02}", secs / 60, secs % 60));
+    }
+    if let Some(score) = level.best_score.filter(|p| p.right + p.wrong > 0) {
+        text.push(format!(" · {}/{}", score.right, score.right + score.wrong));
+    }
+    Some((text, Color::rgb(0., 0.5, 0.)))
+}
+
+pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<AllSettings>, progress: Res<Progress>, locale: Res<Locale>) {
     commands
         .spawn(Camera2dBundle::default())
         .insert(MainMenuMarker);
@@ -248,21 +397,35 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
 
         for p in path.read_dir().unwrap() {
             if let Ok(entry) = p {
-                if entry.path().is_dir() && entry.path().read_dir().unwrap().find(|p| p.as_ref().is_ok_and(|p| {p.file_name().eq("main.gltf")})).is_some() {
-                    if let Ok(file) = File::open(entry.path().join("config.json")) {
-                        let reader = BufReader::new(file);
-                        let mut u = serde_json::from_reader::<_,LevelSchema>(reader).unwrap();
-                        if u.prioritize == 0 {
-                            u.prioritize = u32::MAX;
-                        }
-                        level_paths.push((entry.path(),u));
-                    } else {
-                        level_paths.push((entry.path(),LevelSchema {
-                            name: entry.path().to_string_lossy().to_owned().to_string(),
-                            version: 1,
-                            prioritize: u32::MAX
-                        }));
+                // a level pack stands in for the folder of the same name
+                let folder = if entry.path().is_dir() {
+                    entry.file_name().to_string_lossy().into_owned()
+                } else if entry.path().extension().is_some_and(|p| p == "zip") {
+                    let folder = entry.path().file_stem().unwrap().to_string_lossy().into_owned();
+                    if !level_pack::is_pack(&folder) {
+                        continue;
+                    }
+                    folder
+                } else {
+                    continue;
+                };
+
+                let config = config::read_level_config(&folder);
+                if config::level_entry(&folder, config.as_ref()).is_none() {
+                    continue;
+                }
+                if let Some(mut u) = config {
+                    if u.prioritize == 0 {
+                        u.prioritize = u32::MAX;
                     }
+                    level_paths.push((path.join(&folder),u));
+                } else {
+                    level_paths.push((path.join(&folder),LevelSchema {
+                        name: path.join(&folder).to_string_lossy().to_owned().to_string(),
+                        version: 1,
+                        prioritize: u32::MAX,
+                        ..Default::default()
+                    }));
                 }
             }
         }
@@ -328,7 +491,7 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
     };
 
     let mut text_heading = TextBundle::from_section(
-        "Віртуальна гра 'Подорож з фізикою'",
+        locale.resolve("@menu.title"),
         TextStyle {
             font_size: 64.,
             color: Color::BLACK,
@@ -371,9 +534,9 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
         MainMenuButtonMarker(MainMenuButtonEnum::StartGame)
     );
 
-    let goto_level_picker_text = TextBundle::from_section(
-        "Почати",
-        button_text_style.clone()
+    let goto_level_picker_text = (
+        TextBundle::from_section(locale.resolve("@menu.start"), button_text_style.clone()),
+        LocalizedText::new("@menu.start"),
     );
 
     let goto_about_picker = (
@@ -384,9 +547,9 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
         MainMenuButtonMarker(MainMenuButtonEnum::About)
     );
 
-    let goto_about_picker_text = TextBundle::from_section(
-        "Посібник користувача",
-        button_text_style.clone()
+    let goto_about_picker_text = (
+        TextBundle::from_section(locale.resolve("@menu.manual"), button_text_style.clone()),
+        LocalizedText::new("@menu.manual"),
     );
 
     let goto_settings_picker = (
@@ -397,9 +560,9 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
         MainMenuButtonMarker(MainMenuButtonEnum::Settings)
     );
 
-    let goto_settings_picker_text = TextBundle::from_section(
-        "Налаштування",
-        button_text_style.clone()
+    let goto_settings_picker_text = (
+        TextBundle::from_section(locale.resolve("@menu.settings"), button_text_style.clone()),
+        LocalizedText::new("@menu.settings"),
     );
 
     let settings_node = NodeBundle { 
@@ -465,7 +628,7 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
         .insert((RootNode,MainMenuMarker))
         .with_children(|parent| {
             parent.spawn(main_screen_bg);
-            parent.spawn(text_heading);
+            parent.spawn((text_heading, LocalizedText::new("@menu.title")));
 
             parent
                 .spawn(main_screen_button_group_node)
@@ -522,7 +685,10 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
             parent
                 .spawn(goto_back_main)
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section("Назад", button_text_style.clone()));
+                    parent.spawn((
+                        TextBundle::from_section(locale.resolve("@menu.back"), button_text_style.clone()),
+                        LocalizedText::new("@menu.back"),
+                    ));
                 });
 
             parent.spawn(NodeBundle {
@@ -533,13 +699,13 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                 border_color: bevy::ui::BorderColor(Color::BLACK),
                 ..Default::default()
             }).with_children(|parent| {
-                let mut volume_label = TextBundle::from_section("Гучність: ", button_text_style.clone());
+                let mut volume_label = TextBundle::from_section(locale.resolve("@menu.volume"), button_text_style.clone());
                 volume_label.style.padding = UiRect::all(Val::Px(10.));
                 let mut volume_value_label = TextBundle::from_section(format!("{}",settings.volume), button_text_style.clone());
                 volume_value_label.style.padding = UiRect::all(Val::Px(10.));
 
                 parent
-                    .spawn(volume_label)
+                    .spawn((volume_label, LocalizedText::new("@menu.volume")))
                     .insert(BackgroundColor(Color::WHITE));
                 parent.spawn(bevy::ui::node_bundles::ButtonBundle {
                     style: Style {
@@ -588,13 +754,13 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                 border_color: bevy::ui::BorderColor(Color::BLACK),
                 ..Default::default()
             }).with_children(|parent| {
-                let mut fov_label = TextBundle::from_section("Кут огляду:", button_text_style.clone());
+                let mut fov_label = TextBundle::from_section(locale.resolve("@menu.fov"), button_text_style.clone());
                 fov_label.style.padding = UiRect::all(Val::Px(10.));
                 let mut fov_value_label = TextBundle::from_section(format!("{}",settings.fov), button_text_style.clone());
                 fov_value_label.style.padding = UiRect::all(Val::Px(10.));
 
                 parent
-                    .spawn(fov_label)
+                    .spawn((fov_label, LocalizedText::new("@menu.fov")))
                     .insert(BackgroundColor(Color::WHITE));
                 parent.spawn(bevy::ui::node_bundles::ButtonBundle {
                     style: Style {
@@ -634,6 +800,39 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                     parent.spawn(TextBundle::from_section("+",button_text_style.clone()));
                 });
             });
+
+            parent.spawn(NodeBundle {
+                style: Style {
+                    border: UiRect::all(Val::Px(4.)),
+                    ..Default::default()
+                },
+                border_color: bevy::ui::BorderColor(Color::BLACK),
+                ..Default::default()
+            }).with_children(|parent| {
+                let mut language_label = TextBundle::from_section(locale.resolve("@menu.language"), button_text_style.clone());
+                language_label.style.padding = UiRect::all(Val::Px(10.));
+
+                parent
+                    .spawn((language_label, LocalizedText::new("@menu.language")))
+                    .insert(BackgroundColor(Color::WHITE));
+                parent.spawn(bevy::ui::node_bundles::ButtonBundle {
+                    style: Style {
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(10.)),
+                        ..Default::default()
+                    },
+                    background_color: BackgroundColor(Color::WHITE),
+                    ..Default::default()
+                })
+                .insert(SettingsButtonMarker(super::components::SettingsButtonEnum::Language))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(locale.resolve("@menu.language_name"), button_text_style.clone()),
+                        LocalizedText::new("@menu.language_name"),
+                    ));
+                });
+            });
         });
 
         commands // HELP
@@ -662,7 +861,10 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
             parent
                 .spawn(goto_back_main_2)
                 .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section("Назад", button_text_style.clone()));
+                    parent.spawn((
+                        TextBundle::from_section(locale.resolve("@menu.back"), button_text_style.clone()),
+                        LocalizedText::new("@menu.back"),
+                    ));
                 });
 
             let style = TextStyle {
@@ -677,25 +879,30 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                 color: Color::RED
             };
 
+            // (text, is it a control key)
+            let about = [
+                ("@menu.about.intro", false),
+                ("@menu.about.keys", false),
+                ("W/A/S/D", true),
+                ("@menu.about.move", false),
+                ("@menu.about.mouse", true),
+                ("@menu.about.look", false),
+                ("Space", true),
+                ("@menu.about.jump", false),
+                ("E", true),
+                ("@menu.about.interact", false),
+                ("Escape", true),
+                ("@menu.about.exit", false),
+            ];
+
             parent
                 .spawn(TextBundle::from_sections(
-                    [
-                        TextSection::new("Вітаємо Вас, шановний гравцю, у чарівному світі фізики! За допомогою цієї \
-                                            віртуальної лабораторії ви зможете провести різноманітні експерименти і \
-                                            перевірити власні знання. \n", style.clone()),
-                        TextSection::new("Щоб усішно керувати простором, пропонуємо вивчити кнопки управління: \n - Кнопка ", style.clone()),
-                        TextSection::new("W/A/S/D", hint_style.clone()),
-                        TextSection::new(" викориутстовується для пересування у просторі \n - ", style.clone()),
-                        TextSection::new("Миша", hint_style.clone()),
-                        TextSection::new(" викориутстовується для повороту камери \n - Кнопка ", style.clone()),
-                        TextSection::new("Space", hint_style.clone()),
-                        TextSection::new(" викориутстовується для стрибка \n - Кнопка  ", style.clone()),
-                        TextSection::new("E", hint_style.clone()),
-                        TextSection::new(" викориутстовується для взаємодії з об'єктами \n - Кнопка ", style.clone()),
-                        TextSection::new("Escape", hint_style.clone()),
-                        TextSection::new(" викориутстовується для виходу на головний екран \n", style.clone()),
-                    ]
-                )).insert(BackgroundColor(tools::consts::styles::button::LESS_TRANSPARENT_WHITE));
+                    about.iter().map(|(text, is_key)| {
+                        TextSection::new(locale.resolve(text), if *is_key { hint_style.clone() } else { style.clone() })
+                    })
+                ))
+                .insert(LocalizedText(about.iter().map(|p| p.0.to_owned()).collect()))
+                .insert(BackgroundColor(tools::consts::styles::button::LESS_TRANSPARENT_WHITE));
             
             parent.spawn(ImageBundle {
                 image: UiImage { texture: asset_server.load("internal/textures/keyboard-layout.png"), flip_x: false, flip_y: false },
@@ -741,7 +948,7 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
             });
 
             let mut text_heading = TextBundle::from_section(
-                "Список лекцій",
+                locale.resolve("@menu.levels"),
                 TextStyle {
                     font_size: 58.,
                     color: Color::BLACK,
@@ -750,11 +957,11 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
             );
             text_heading.style.margin = UiRect::vertical(Val::Px(35.));
             text_heading.style.align_self = AlignSelf::Center;
-            parent.spawn(text_heading);
+            parent.spawn((text_heading, LocalizedText::new("@menu.levels")));
 
             parent.spawn((NodeBundle {
                 style: Style {
-                    width: Val::Percent(100.),
+                    width: Val::Percent(65.),
                     display: bevy::ui::Display::Flex,
                     flex_wrap: FlexWrap::Wrap,
                     // grid_auto_flow: bevy::ui::GridAutoFlow::Row,
@@ -773,9 +980,30 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                     font: asset_server.load(font_names::NOTO_SANS_SM_BOLD),
                 };
 
+                let levels = level_paths
+                    .iter()
+                    .map(|p| (p.0.file_name().unwrap().to_string_lossy().into_owned(), p.1.clone()))
+                    .collect::<Vec<_>>();
+                let completed = progress.completed();
+
                 for p in level_paths {
-                    
-                    parent.spawn(ButtonBundle {
+                    let folder = p.0.file_name().unwrap().to_string_lossy().into_owned();
+                    let problem = p.1.version_problem();
+                    let locked = problem.is_some() || !config::is_level_unlocked(&folder, &levels, &completed);
+                    let badge = match problem {
+                        Some(problem) => Some((vec![problem], Color::RED)),
+                        None => level_badge(&folder, &levels, &progress, locked),
+                    };
+                    let image: Handle<Image> = asset_server.load(
+                        match p.1.thumbnail.as_ref().filter(|t| level_pack::level_has_file(&folder, t)) {
+                            Some(thumbnail) => PathBuf::from("levels").join(&folder).join(thumbnail),
+                            None if level_pack::level_has_file(&folder, "preview.png") => {
+                                PathBuf::from("levels").join(&folder).join("preview.png")
+                            }
+                            None => "internal/splash/preview.png".into(),
+                        });
+
+                    let mut button = parent.spawn(ButtonBundle {
                         style: Style {
                             width: Val::Percent(25.),
                             flex_grow: 0.,
@@ -784,16 +1012,18 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                             flex_direction: FlexDirection::Column,
                             ..Default::default()
                         },
-                        background_color: BackgroundColor(Color::ORANGE_RED),
+                        background_color: BackgroundColor(if locked { Color::GRAY } else { Color::ORANGE_RED }),
                         ..Default::default()
-                    }).insert(Level(p.0.components().last().unwrap().as_os_str().to_os_string())).with_children(|el| {
+                    });
+                    if locked {
+                        button.insert(LockedLevel);
+                    }
+                    button.insert((
+                        Level(p.0.components().last().unwrap().as_os_str().to_os_string()),
+                        LevelDetails { config: p.1.clone(), image: image.clone() },
+                    )).with_children(|el| {
                         el.spawn(ImageBundle {
-                            image: UiImage::new(asset_server.load(
-                                if p.0.clone().join("preview.png").exists() {
-                                    p.0.clone().join("preview.png")
-                                } else {
-                                    "internal/splash/preview.png".into()
-                                })),
+                            image: UiImage::new(image),
                             style: Style {
                                 width: Val::Percent(100.),
                                 height: Val::Auto,
@@ -815,10 +1045,68 @@ pub fn prepare_main_menu(mut commands: Commands, asset_server: Res<AssetServer>,
                         text.text.alignment = bevy::text::TextAlignment::Center;
                         text.text.linebreak_behavior = BreakLineOn::AnyCharacter;
                         el.spawn(text);
+
+                        if let Some((badge, color)) = badge {
+                            let badge_style = TextStyle {
+                                font_size: 22.,
+                                color,
+                                font: asset_server.load(font_names::NOTO_SANS_MEDIUM),
+                            };
+                            let mut text = TextBundle::from_sections(
+                                badge.iter().map(|p| TextSection::new(locale.resolve(p), badge_style.clone())),
+                            );
+                            text.style.width = Val::Percent(80.);
+                            text.style.margin = UiRect {
+                                left: Val::Percent(10.),
+                                right: Val::Percent(10.),
+                                top: Val::Px(5.),
+                                bottom: Val::Px(10.),
+                            };
+                            text.text.alignment = bevy::text::TextAlignment::Center;
+                            el.spawn((text, LocalizedText(badge)));
+                        }
                     });
                     println!("{}",p.0.display());
                 }
             });
+
+            parent.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(60.),
+                    top: Val::Px(160.),
+                    bottom: Val::Px(60.),
+                    width: Val::Percent(30.),
+                    padding: UiRect::all(Val::Px(20.)),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(consts::styles::button::LESS_TRANSPARENT_WHITE),
+                ..Default::default()
+            }).with_children(|parent| {
+                parent.spawn((ImageBundle {
+                    style: Style {
+                        display: bevy::ui::Display::None,
+                        width: Val::Percent(40.),
+                        aspect_ratio: Some(1.),
+                        align_self: AlignSelf::Center,
+                        margin: UiRect::bottom(Val::Px(15.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }, LevelDetailsImage));
+
+                let mut text = TextBundle::from_section(
+                    locale.resolve("@menu.details_placeholder"),
+                    TextStyle {
+                        font_size: 24.,
+                        color: Color::DARK_GRAY,
+                        font: asset_server.load(font_names::NOTO_SANS_MEDIUM),
+                    },
+                );
+                text.style.max_width = Val::Percent(100.);
+                parent.spawn((text, LevelDetailsText, LocalizedText::new("@menu.details_placeholder")));
+            });
         });
 }
 
@@ -3,6 +3,8 @@ pub mod broadcast;
 pub mod camera;
 pub mod hint_overlay;
 pub mod ingame_ui;
+pub mod interaction;
+pub mod localization;
 pub mod main_menu;
 // pub mod placing_parts;
 pub mod player_control;
@@ -8,11 +8,11 @@ use super::super::tools::markers::ExploredLightObjectMarker;
 use super::super::tools::{
      transition::TransitionMarker,
 };
-use super::components::GltfFileMarker;
+use super::components::{GltfFileMarker, LightBaseline};
 
 
 use bevy::prelude::{
-     EventReader, PointLight,
+     DirectionalLight, EventReader, PointLight, SpotLight,
 };
 
 use bevy::{
@@ -136,14 +136,40 @@ pub fn attach_collision_groups(
 
 pub fn gltf_adjust_light(
     mut commands: Commands,
-    mut gltf_node_q: Query<(Entity, &mut PointLight), Without<ExploredLightObjectMarker>>,
+    point_q: Query<(Entity, &PointLight), Without<ExploredLightObjectMarker>>,
+    spot_q: Query<(Entity, &SpotLight), Without<ExploredLightObjectMarker>>,
+    directional_q: Query<(Entity, &DirectionalLight), Without<ExploredLightObjectMarker>>,
 ) {
-    // for mut e in gltf_node_q.iter_mut() {
-    //     e.1.intensity /= 720.;
-    //     commands.entity(e.0).insert(ExploredLightObjectMarker);
-    //     // e.1.shadows_enabled = true;
-    //     // e.1.range = 200.;
-    // }
+    for (e, light) in point_q.iter() {
+        commands.entity(e).insert((
+            LightBaseline {
+                intensity: light.intensity,
+                color: light.color,
+                range: Some(light.range),
+            },
+            ExploredLightObjectMarker,
+        ));
+    }
+    for (e, light) in spot_q.iter() {
+        commands.entity(e).insert((
+            LightBaseline {
+                intensity: light.intensity,
+                color: light.color,
+                range: Some(light.range),
+            },
+            ExploredLightObjectMarker,
+        ));
+    }
+    for (e, light) in directional_q.iter() {
+        commands.entity(e).insert((
+            LightBaseline {
+                intensity: light.illuminance,
+                color: light.color,
+                range: None,
+            },
+            ExploredLightObjectMarker,
+        ));
+    }
 }
 
 pub fn prepare_rapier(mut r_ctx: ResMut<RapierContext>) {
@@ -1,18 +1,22 @@
 use super::broadcast::Actor;
+use super::broadcast::requires_item;
 use super::super::tools::collision_groups;
 use super::super::tools::events::{AttachCollider, ModifyCollisionGroup};
 use super::super::tools::{
     events::SpawnPlayer, markers::ExploredGLTFObjectMarker,
+    resources::{LoadingSceneInfo, Progress},
 };
+use super::components::PrefabOverrides;
 use super::custom_properties::CustomProps;
 use crate::lib::audio::CollisionAudio;
 use bevy::gltf::GltfExtras;
 use bevy::pbr::{CascadeShadowConfigBuilder, NotShadowCaster, NotShadowReceiver};
 use bevy::prelude::{
     
-    Children, DirectionalLight, DirectionalLightBundle,
-     Visibility, EntityWorldMut, PointLight, SpotLight,
+    BuildChildren, Children, DirectionalLight, DirectionalLightBundle,
+     Visibility, EntityWorldMut, PointLight, SpotLight, Vec3,
 };
+use bevy::scene::SceneBundle;
 use bevy::utils::HashMap;
 use bevy::{
     pbr::DirectionalLightShadowMap,
@@ -25,7 +29,7 @@ use bevy_rapier3d::prelude::{
      ColliderMassProperties,
     Sensor, Sleeping, Velocity,
 };
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 
 
@@ -49,6 +53,8 @@ pub fn gltf_load_extras(
     mut mesh_collider_ev_w: EventWriter<AttachCollider>,
     mut mesh_collision_group_ev_w: EventWriter<ModifyCollisionGroup>,
     ass: Res<AssetServer>,
+    progress: Res<Progress>,
+    scene_info: Option<Res<LoadingSceneInfo>>,
 ) {
     for mut node in gltf_node_q.iter_mut() {
         commands.entity(node.0).insert(ExploredGLTFObjectMarker);
@@ -56,12 +62,44 @@ pub fn gltf_load_extras(
         let object = serde_json::from_str::<Value>(node.1.value.as_str()).unwrap();
         let object = object.as_object().unwrap();
 
+        // a placeholder, everything else on it is for the prefab
+        if let Some(include) = object.get_key_value("include_scene") {
+            if let CustomProps::IncludeScene(path) = CustomProps::convert(include.0, include.1, &object) {
+                let mut overrides = object.clone();
+                overrides.remove("include_scene");
+                commands.entity(node.0).with_children(|p| {
+                    p.spawn((
+                        SceneBundle {
+                            scene: ass.load(format!("prefabs/{}#Scene0", path)),
+                            ..Default::default()
+                        },
+                        PrefabOverrides(overrides),
+                        Name::new(format!("Prefab {}", path)),
+                    ));
+                });
+                continue;
+            }
+        }
+
+        if requires_item::required_item(object).is_some() && requires_item::gated_action(object).is_none() {
+            println!("#requires_item on {:?} has no action to gate", node.4);
+        }
+
         for extra in object.iter() {
             match CustomProps::convert(extra.0, extra.1, &object) {
                 CustomProps::_Resolved => {}
                 CustomProps::PlayerSpawnPoint { ambient, skybox, diffuse, specular } => {
+                    // an unfinished level is continued from the last action:checkpoint
+                    let checkpoint = scene_info
+                        .as_ref()
+                        .and_then(|p| progress.levels.get(&p.name))
+                        .and_then(|p| p.checkpoint);
+                    let translation = match checkpoint {
+                        Some(p) => Vec3::from(p),
+                        None => node.2.clone().translation,
+                    };
                     player_creation_ev_w.send(SpawnPlayer {
-                        transform: Transform::from_translation(node.2.clone().translation),
+                        transform: Transform::from_translation(translation),
                         camera_params: (ambient, skybox, diffuse, specular),
                     });
 
@@ -185,6 +223,7 @@ pub fn gltf_load_extras(
                     });
                     // commands.entity(node.0).insert(Actor(Box::new(OpenDoorAction::default())));
                 }
+                CustomProps::IncludeScene(_) => {}
                 CustomProps::_Unhandled => {
                     // println!("UNHANDLED {:#?}: {:#?}", extra.0, extra.1);
                 }
@@ -192,3 +231,65 @@ pub fn gltf_load_extras(
         }
     }
 }
+
+
+/// Writes placeholder extras into a freshly spawned prefab, before `gltf_load_extras` sees it
+pub fn apply_prefab_overrides(
+    mut commands: Commands,
+    prefab_q: Query<(Entity, &PrefabOverrides, Option<&Children>)>,
+    children_q: Query<&Children>,
+    mut extras_q: Query<&mut GltfExtras, Without<ExploredGLTFObjectMarker>>,
+) {
+    for (entity, overrides, children) in prefab_q.iter() {
+        // prefab file is still loading
+        let Some(children) = children else {
+            continue;
+        };
+        commands.entity(entity).remove::<PrefabOverrides>();
+
+        let mut nodes = vec![];
+        let mut stack = children.iter().map(|p| (*p, 0)).collect::<Vec<_>>();
+        while let Some((e, depth)) = stack.pop() {
+            if extras_q.contains(e) {
+                nodes.push((e, depth));
+            }
+            if let Ok(children) = children_q.get(e) {
+                stack.extend(children.iter().map(|p| (*p, depth + 1)));
+            }
+        }
+        let Some(top) = nodes.iter().map(|p| p.1).min() else {
+            println!("Prefab has no extras to override");
+            continue;
+        };
+
+        let mut parsed = nodes
+            .iter()
+            .map(|(e, depth)| {
+                let extras = extras_q.get(*e).unwrap();
+                let object = serde_json::from_str::<Map<String, Value>>(&extras.value).unwrap();
+                (*e, *depth, object)
+            })
+            .collect::<Vec<_>>();
+
+        for (key, value) in overrides.0.iter() {
+            let mut found = false;
+            for (_, _, object) in parsed.iter_mut() {
+                if let Some(v) = object.get_mut(key) {
+                    *v = value.clone();
+                    found = true;
+                }
+            }
+            if !found {
+                for (_, depth, object) in parsed.iter_mut() {
+                    if *depth == top {
+                        object.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+
+        for (e, _, object) in parsed {
+            extras_q.get_mut(e).unwrap().value = Value::Object(object).to_string();
+        }
+    }
+}
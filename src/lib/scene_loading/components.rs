@@ -1,7 +1,29 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Color, Component};
 
 #[derive(Component)]
 pub struct GltfFileMarker;
 
 #[derive(Component)]
-pub struct MainSceneMarker;
\ No newline at end of file
+pub struct MainSceneMarker;
+/// How a light came out of the glTF, so actions know what "on" looks like
+#[derive(Component, Clone, Copy)]
+pub struct LightBaseline {
+    /// Lumens for point and spot lights, lux for directional ones
+    pub intensity: f32,
+    pub color: Color,
+    /// Directional lights have none
+    pub range: Option<f32>,
+}
+
+/// Scene instanced on top of the main one, goes away together with the level
+#[derive(Component)]
+pub struct SubSceneMarker;
+
+/// Put on a prefab instance by `include_scene`, extras of the placeholder node
+/// that are written into the prefab nodes once it is spawned
+#[derive(Component)]
+pub struct PrefabOverrides(pub serde_json::Map<String, serde_json::Value>);
+
+/// Root of a loaded level section, see `sections.rs`
+#[derive(Component)]
+pub struct SectionMarker(pub String);
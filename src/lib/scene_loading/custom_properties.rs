@@ -1,6 +1,6 @@
 
 use super::attachements::ColliderType;
-use super::broadcast::{self, Action};
+use super::broadcast::{self, requires_item, Action};
 use bevy::prelude::Color;
 use bevy_rapier3d::prelude::{
     RigidBody,
@@ -26,6 +26,10 @@ use serde_json::Value;
 /// is_visible: true
 /// collider_sensor: true
 ///
+/// include_scene: string - glTF from assets/prefabs (".gltf" may be left out) put under this node.
+///     All other extras of this node override the same ones in the prefab nodes,
+///     those no prefab node has are added to its top nodes
+///
 /// sun_marker: true
 ///     | sun_intensity: f32
 ///     | sun_color: (f32,f32,f32,f32)
@@ -42,8 +46,8 @@ use serde_json::Value;
 ///
 ///
 ///
-/// action:open_door: bool
-/// action:key_door: u16 (key number)
+/// action:open_door: string | u64 ("openable" | "obstructed" | "broken" | signal id)
+/// action:key_door: string | u64 (item id, same as open_door + #requires_item)
 /// action:stand_button: u16 (key number)
 /// action:press_button: u16 (key number)
 ///
@@ -52,13 +56,31 @@ use serde_json::Value;
 ///
 ///  ============== ACTIONS ==========
 ///
+/// Every hint and name below may be "@key" or "@key:a|b" instead of plain text.
+///     The key is looked up in levels/<level>/lang/<language>.json, then in assets/lang/<language>.json,
+///     a and b fill the {} of the found text in order. Language is picked in the settings
+///
 /// ::stand_button = u64 - on press, fires an event with that key number
 /// ::stand_button#press = u64 - how long will it be pressed? (0 for toggle button)
 /// ::stand_button#cooldown = u64 - how often you can press button
 ///
-/// ::open_door
-/// ::open_door#keyed = u64 - if 0, not keyed
-/// ::open_door#
+/// ::open_door = "openable" - player opens and closes it with E
+/// ::open_door = "obstructed" - opens while anything is in the doorway, closes once it is clear
+/// ::open_door = "broken" - never opens
+/// ::open_door = u64 - opens while a button with that id is pressed
+/// ::open_door#keyed = u64 | string - item id, if 0, not keyed (same as #requires_item)
+/// ::open_door#door_hint = string - shown while looked at
+/// ::open_door#door_locked_hint = string - shown while locked or while player lacks #requires_item, "@hint.locked" by default
+/// ::open_door#door_auto_close = f64 - closes by itself that many seconds after opening
+/// ::open_door#door_lock_signal = u64 - locked (and closed) until a button with that id is pressed
+/// ::open_door#outline_locked_color = [f32;4] - rgba outline while locked, red by default
+/// no door closes while something stands in the doorway
+///
+/// #interaction_range = f64 - how close player has to be to use open_door, stand_button, link, input_field
+///
+/// #outline = bool - false if it should not be outlined while looked at
+/// #outline_color = [f32;4] - rgba, green by default
+/// #outline_thickness = f64 - in pixels, up to 15
 ///
 ///
 /// action:collision_audio = string
@@ -74,6 +96,154 @@ use serde_json::Value;
 /// 
 /// action:collision_button = u64
 ///
+/// action:pickup = string - item id, picked up into inventory on E
+/// #pickup_name = string - shown in hints and inventory, id by default
+/// #pickup_icon = string - image path relative to assets
+/// #pickup_count = u64
+/// #pickup_signal = u64 - fired once it is picked up
+///
+/// #requires_item = string - one action on the node only executes while player holds the item,
+///     the first of key_door, open_door, stand_button, pickup, link, input_field it has
+/// #requires_item_action = string - which of those it is instead, e.g. "stand_button"
+/// #requires_item_name = string - shown in the hint, id by default
+/// #consume_item = bool - item is taken once used
+///
+/// action:animation = string - clip name from the glTF, empty for the clip animating this node
+/// #anim_loop = "once" | "loop" | "ping_pong"
+/// #anim_speed = f64 - negative plays backwards
+/// #anim_autoplay = bool - starts once the level is loaded
+/// #anim_play = u64 - on press plays forward, from where it is
+/// #anim_reverse = u64 - on press plays backward, from where it is
+/// #anim_hold = u64 - forward while pressed, backward once released (doors, lifts)
+/// #anim_pause = u64 - paused while pressed
+/// #anim_stop = u64 - on press stops where it is
+/// #anim_speed_signals = [[u64, f64], ...] - on press of id, speed is set
+/// #anim_seek_signals = [[u64, f64], ...] - on press of id, jumps to time in seconds
+/// #anim_started_signal = u64 - fired when playback starts or changes direction
+/// #anim_finished_signal = u64 - fired every time the clip reaches its end
+/// #anim_markers = [[f64, u64] | {"name", "time"?, "signal"?, "event"?}, ...] - fires signal id and/or
+///     CustomEvent named event when playback passes time, in either direction.
+///     levels/<level>/animation_markers.json next to main.gltf declares markers per clip name:
+///     { "Lift": [{ "name": "floor_2", "time": 3.5, "signal": 12, "event": "lift_floor_2" }] },
+///     extras may give those a signal or event, referring to them by name only
+/// one_animation, named_animation and full_animation are old forms of it, do not use
+///
+/// action:light = u64 - signal id, on a light or a node with lights under it (point, spot, directional)
+/// #light_mode = "toggle" | "fade" | "flicker" - toggle switches on every press,
+///     fade and flicker are on while pressed, flicker flickers while on
+/// #light_on = bool - is it on at the start, false by default
+/// #light_duration = f64 - seconds to fade in or out, 0.5 for fade and flicker, 0 for toggle
+/// #light_intensity = f64 - intensity when on, from the glTF by default
+/// #light_off_intensity = f64 - part of it left when off, 0 by default
+/// #light_color = [f32;4] - rgba when on, from the glTF by default
+/// #light_off_color = [f32;4] - rgba when off
+/// #light_range = f64 - range when on (not for directional)
+/// #light_flicker_speed = f64
+/// #light_flicker_amount = f64 - 0..1, how much of the intensity flickering takes
+///
+/// action:material = u64 - signal id, changed while pressed, back once released.
+///     Every mesh under the node gets its own copy of the material
+/// #material_target = Name (string) - node whose meshes change, this one by default
+/// #material_color = [f32;4] - rgba base color
+/// #material_emissive = [f32;4] - rgba emissive color
+/// #material_emissive_strength = f64 - multiplies emissive
+/// #material_swap = string - named material from the glTF put instead, switches halfway through duration
+/// #material_duration = f64 - seconds to tween
+/// #material_latch = bool - stays changed after the first press
+///
+/// action:spawner = u64 - signal id, on press puts a copy at the spawner.
+///     Copies get their colliders and actions from their own extras
+/// #spawn_scene = string - name of another scene in main.gltf
+/// #spawn_node = string - or a node of the level, with everything under it
+/// #spawn_file = string - or a .gltf in the level folder, its first scene is used
+/// #spawn_velocity = [f32;3] - initial velocity of dynamic rigidbodies in it, in spawner's space
+/// #spawn_max = u64 - how many copies may exist, the oldest is removed. 0 for no limit
+///
+/// action:section_trigger = string | [string] - sections loaded once player walks into this sensor.
+///     Sections are listed in config.json:
+///     "sections": [{ "name": "hall", "scene": "Hall" | "file": "hall.gltf", "preload": false }]
+///     scene is a named scene in main.gltf, file is a glTF in the level folder
+/// #section_unload = string | [string] - sections unloaded once player walks in
+/// #section_unload_on_exit = bool - loaded sections are unloaded again once player walks out
+///
+/// action:load_level = string - level folder to go to, "next" for next of config.json.
+///     Fades out and loads it once player walks into this sensor. A level that is locked or missing is refused with a hint.
+///     config.json: "next": string, "requires": [string], "unlocks": [string] - level folders.
+///     A level opens once all it requires are completed and, if some levels unlock it, one of those is
+/// #load_level_signal = u64 - goes on this signal instead
+/// #load_level_fade = f64 - seconds of the fade, 1 by default
+/// #carry_inventory = bool - inventory goes along
+/// #carry_score = bool - test chamber answers go along
+/// #complete_on_leave = bool - the current level is marked completed once the portal lets player through
+///
+/// action:complete_level = true | u64 - marks the level completed in assets/data/progress.json
+///     once player walks into this sensor, or on that signal. Best time and test chamber score are kept
+/// #complete_level_hint = string - shown for a moment after, "@hint.level_completed" by default, "" for none
+///
+/// action:checkpoint = true - saves this sensor's position once player walks in,
+///     next time the level is loaded player appears here instead of spawn_point. Cleared on completion
+///
+/// action:input_field = string - name of the answer event. E opens the field, Enter sends it, Escape closes it.
+///     Any language can be typed, with arrows, Shift for selection, Home/End, Backspace/Delete, Ctrl+A and Ctrl+V
+///
+/// action:test_chamber = u64 - answers are compared after NFKC normalization and lowercasing,
+///     whitespace is trimmed and collapsed and any apostrophe counts as '
+/// #answer_ignore_whitespace = bool - "10 м" is the same as "10м"
+/// #answer_ignore_punctuation = bool - "м/с." is the same as "мс"
+/// #answer_type = "text" | "number" - with "number" answers are written like "0.5 A" and "0,5", "5e-1 A",
+///     "5·10^-1 A" and "500 mA" all match it. SI prefixes and units may be latin or Ukrainian ("500 мА", "0,5 А", "36 км/год")
+/// #answer_tolerance = f64 - how far off a number may be, as a fraction of the answer, 0.01 by default
+/// #answer_tolerance_absolute = f64 - or that much in the unit of the answer
+/// #answer_unit_required = bool - "0,5" alone is a wrong unit
+/// #answer_wrong_value_signal = u64 - pressed along with the wrong path if the text or number is wrong
+/// #answer_wrong_unit_signal = u64 - ... if the unit is wrong or missing
+/// #answer_unreadable_signal = u64 - ... if no number could be read
+///
+/// action:quiz = string - name of the input_field answering a question drawn from questions.json in the level folder:
+///     [{ "id": "ohm_1", "text": "...", "type": "text" | "number" | "choice", "answers": ["0.5 A"],
+///        "choices": ["..."], "explanation": "...", "difficulty": 1-5, "tags": ["ohm"],
+///        "tolerance": f64, "tolerance_absolute": f64, "unit_required": bool }]
+///     The question (and choices A), B), ...) floats over this node, the explanation is added once answered.
+///     A choice is answered by its text or letter. Questions are drawn without repeats,
+///     config.json "question_seed": u64 makes the draw the same each time.
+///     The #answer_* extras of test_chamber work here too
+/// #quiz_tags = string | [string] - draw among questions with any of these tags, all by default
+/// #quiz_difficulty = [u8;2] - min and max difficulty
+/// #quiz_right_signal = u64 - pressed on a right answer
+/// #quiz_wrong_signal = u64 - pressed on a wrong one
+/// #quiz_text_distance = f64 - how close player has to be to see the question, 6 by default
+///
+/// action:problem = string - same as quiz, but the question is made from a template of problems.json:
+///     [{ "id": "ohm_law", "text": "U = {U} В, R = {R} Ом. Знайдіть силу струму",
+///        "variables": { "U": { "min": 1, "max": 12, "step": 0.5 }, "R": { "min": 2, "max": 20 } },
+///        "formula": "U / R", "unit": "А", "explanation": "I = U / R = {answer} А",
+///        "tolerance": f64, "tolerance_absolute": f64, "unit_required": bool, "difficulty": 1-5, "tags": [] }]
+///     Values are rolled with the same seeded generator, the answer is checked as a number with a unit.
+///     Formulas know + - * / ^, parentheses, sqrt, abs, sin, cos, tan, asin, acos, atan, ln, log, exp and pi.
+///     Takes the same #quiz_* and #answer_* extras
+///
+/// action:multiple_choice = [u64] - stand_button or collision_button ids, one per answer option.
+///     Options are shuffled onto the buttons with the seeded generator of questions.json.
+///     A right press ends it, a wrong one shows the explanation (or "@hint.try_again") and uses an attempt.
+///     Once attempts run out the right option turns green and the explanation is shown
+/// #choice_text = string - the question, with #choice_options it is not drawn from questions.json
+/// #choice_options = [string]
+/// #choice_answers = string | [string] - right options
+/// #choice_explanation = string
+/// #quiz_tags, #quiz_difficulty - which "choice" question of questions.json to draw otherwise
+/// #choice_labels = [string] - names of nodes the options float over, in button order.
+///     Without them the options are listed under the question as A), B), ... in button order
+/// #choice_attempts = u64 - 2 by default, 0 for no limit
+/// #choice_right_signal = u64 - pressed on the right answer
+/// #choice_wrong_signal = u64 - pressed on each wrong one
+/// #choice_failed_signal = u64 - pressed once attempts run out
+/// #quiz_text_distance = f64 - how close player has to be to see the texts, 6 by default
+///
+/// action:grabbable = true - only for rigidbody: "Dynamic", mass comes from density
+/// #grab_distance = f64 - how far in front of the camera it is held
+/// #grab_stiffness = f64 - how strongly it is pulled to that point
+/// #throw_impulse = f64 - impulse of a fully charged throw (LMB held for 1.5s)
+///
 pub enum CustomProps {
     // todo!() why names of object are included?
     _Unhandled,
@@ -96,6 +266,7 @@ pub enum CustomProps {
     },
     MassProp(f32),
     CollisionAudio(String), // todo volume etc...
+    IncludeScene(String),
 
     Action(Box<dyn Action>),
 }
@@ -239,6 +410,13 @@ impl CustomProps {
         if name == "audio_on_collision" && value.is_string() {
             return CustomProps::CollisionAudio(value.as_str().unwrap().to_string());
         }
+        if name == "include_scene" && value.is_string() {
+            let mut path = value.as_str().unwrap().to_string();
+            if !path.ends_with(".gltf") && !path.ends_with(".glb") {
+                path.push_str(".gltf");
+            }
+            return CustomProps::IncludeScene(path);
+        }
         if name == "density" && value.is_f64() {
             return CustomProps::MassProp(value.as_f64().unwrap() as f32);
         }
@@ -251,8 +429,8 @@ impl CustomProps {
         if a.get(0) == Some(&&"action") && a.get(1).is_some() && a.get(2).is_none() {
             match a[1] {
                 "open_door" => {
-                    let  a = broadcast::open_door::OpenDoorAction::new(value.clone(), &main);
-                    return CustomProps::Action(Box::new(a));
+                    let a = requires_item::build::<broadcast::open_door::OpenDoorAction>(a[1], value.clone(), &main);
+                    return CustomProps::Action(a);
                 }
                 "ball_falling_01" => {
                     let a =
@@ -260,9 +438,9 @@ impl CustomProps {
                     return CustomProps::Action(Box::new(a));
                 }
                 "stand_button" => {
-                    let  a =
-                        broadcast::stand_button::StandButtonAction::new(value.clone(), &main);
-                    return CustomProps::Action(Box::new(a));
+                    let a =
+                        requires_item::build::<broadcast::stand_button::StandButtonAction>(a[1], value.clone(), &main);
+                    return CustomProps::Action(a);
                 }
                 "collision_button" => {
                     let a =
@@ -288,19 +466,41 @@ impl CustomProps {
                     );
                     return CustomProps::Action(Box::new(a));
                 }
-                "one_animation" => {
-                    let  a =
-                        broadcast::one_animation::OneAnimationAction::new(value.clone(), &main);
+                "animation" => {
+                    let a = broadcast::animation::AnimationAction::new(value.clone(), &main);
                     return CustomProps::Action(Box::new(a));
                 }
-                "full_animation" => {
-                    let  a =
-                        broadcast::full_animation::FullAnimationAction::new(value.clone(), &main);
+                "light" => {
+                    let a = broadcast::light_animation::LightAnimationAction::new(value.clone(), &main);
                     return CustomProps::Action(Box::new(a));
                 }
-                "named_animation" => {
-                    let  a =
-                        broadcast::named_animation::NamedAnimationAction::new(value.clone(), &main);
+                "material" => {
+                    let a = broadcast::material::MaterialAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "spawner" => {
+                    let a = broadcast::spawner::SpawnerAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "section_trigger" => {
+                    let a = broadcast::section_trigger::SectionTriggerAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "load_level" => {
+                    let a = broadcast::load_level::LoadLevelAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "complete_level" => {
+                    let a = broadcast::complete_level::CompleteLevelAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "checkpoint" => {
+                    let a = broadcast::checkpoint::CheckpointAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "one_animation" | "full_animation" | "named_animation" => {
+                    let a =
+                        broadcast::animation::AnimationAction::legacy(a[1], value.clone(), &main);
                     return CustomProps::Action(Box::new(a));
                 }
                 "delay_trasmitter" => {
@@ -314,20 +514,52 @@ impl CustomProps {
                     return CustomProps::Action(Box::new(a));
                 }
                 "link" => {
-                    let a = 
-                    broadcast::link_opener::LinkOpenerAction::new(value.clone(), &main);
-                    return CustomProps::Action(Box::new(a));
+                    let a =
+                        requires_item::build::<broadcast::link_opener::LinkOpenerAction>(a[1], value.clone(), &main);
+                    return CustomProps::Action(a);
                 }
                 "input_field" => {
-                    let  a = 
-                        broadcast::input_field::InputFieldAction::new(value.clone(), &main);
-                    return CustomProps::Action(Box::new(a));
+                    let a =
+                        requires_item::build::<broadcast::input_field::InputFieldAction>(a[1], value.clone(), &main);
+                    return CustomProps::Action(a);
                 }
                 "test_chamber" => {
                     let a = 
                         broadcast::test_chamber::TestChamberAction::new(value.clone(), &main);
                     return CustomProps::Action(Box::new(a));
                 }
+                "quiz" => {
+                    let a = broadcast::quiz::QuizAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "multiple_choice" => {
+                    let a = broadcast::multiple_choice::MultipleChoiceAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
+                "problem" => {
+                    let mut a = broadcast::quiz::QuizAction::new(value.clone(), &main);
+                    a.generated = true;
+                    a.name = "problem".into();
+                    return CustomProps::Action(Box::new(a));
+                }
+                "pickup" => {
+                    let a = requires_item::build::<broadcast::pickup::PickupAction>(a[1], value.clone(), &main);
+                    return CustomProps::Action(a);
+                }
+                "key_door" => {
+                    // same as a player opened door, #requires_item is taken from this value
+                    let a = requires_item::build::<broadcast::open_door::OpenDoorAction>(
+                        a[1],
+                        Value::from("openable"),
+                        &main,
+                    );
+                    return CustomProps::Action(a);
+                }
+                "grabbable" => {
+                    let a =
+                        broadcast::grabbable::GrabbableAction::new(value.clone(), &main);
+                    return CustomProps::Action(Box::new(a));
+                }
 
                 unknown => {
                     println!("There is some custom property unhandled! Name is {}", unknown);
@@ -3,16 +3,21 @@ use std::time::Duration;
 
 use super::super::tools::events::LoadLevel;
 
-use super::super::tools::resources::LoadingSceneInfo;
+use super::super::tools::config::{self, AnimationMarkersSchema, SceneRef};
+use super::super::tools::{level_pack, questions};
+use super::super::tools::resources::{AnimationMarkers, CurrentLevelConfig, LevelTransition, LoadingSceneInfo, QuestionBank};
 use super::super::tools::{
      transition::TransitionMarker,
 };
 use super::components::MainSceneMarker;
+use super::level_transition::{self, LevelFadeMarker};
+use super::sections;
 
 use crate::GameState;
 use crate::lib::scene_loading::components::GltfFileMarker;
+use bevy::asset::LoadState;
 use bevy::gltf::Gltf;
-use bevy::prelude::{ State, NextState, EventReader };
+use bevy::prelude::{ State, NextState, EventReader, DespawnRecursiveExt, Entity, Query, With };
 use bevy::{
     prelude::{
         AssetServer, Assets,  Commands, 
@@ -25,17 +30,38 @@ use bevy::{
 pub fn load_gltf_file(
     mut commands: Commands, 
     asset: Res<AssetServer>,
-    mut ev: EventReader<LoadLevel>
+    mut ev: EventReader<LoadLevel>,
+    mut transition: ResMut<LevelTransition>,
+    mut fade_q: Query<(&mut LevelFadeMarker, &mut TransitionMarker)>,
 ) {
     if ev.len() > 1 {
         panic!("You should not load 2 gltfs at the same tick... or in general");
     }
 
     for i in  ev.read() {
+        let level_config = config::read_level_config(&i.string.to_string_lossy());
+        if let Some(level_config) = level_config.as_ref().filter(|p| p.version_problem().is_some()) {
+            // version_problem is a locale key for the player, the log gets it in plain words
+            println!(
+                "{}: level version {} is not supported, this build opens versions {} to {}",
+                i.string.to_string_lossy(),
+                level_config.version,
+                config::LEVEL_VERSIONS.start(),
+                config::LEVEL_VERSIONS.end()
+            );
+            level_transition::cancel_transition(&mut transition, &mut fade_q);
+            continue;
+        }
+        let Some(entry) = config::level_entry(&i.string.to_string_lossy(), level_config.as_ref()) else {
+            println!("{}: no main.gltf or main.glb", i.string.to_string_lossy());
+            level_transition::cancel_transition(&mut transition, &mut fade_q);
+            continue;
+        };
+
         let mut s = PathBuf::new();
         s = s.join("levels");
         s = s.join(i.string.clone());
-        s = s.join("main.gltf");
+        s = s.join(entry);
         println!("Loading {}",s.display());
         let glb = asset.load(s);
         commands.insert_resource(LoadingSceneInfo {
@@ -43,6 +69,36 @@ pub fn load_gltf_file(
             is_loaded: false,
             name: i.string.clone().to_string_lossy().into_owned()
         });
+
+        let markers = match level_pack::read_level_file(&i.string.to_string_lossy(), "animation_markers.json") {
+            Some(file) => match serde_json::from_slice::<AnimationMarkersSchema>(&file) {
+                Ok(p) => p,
+                Err(err) => {
+                    println!(
+                        "animation_markers.json of {} is not {{ \"clip\": [{{ \"name\", \"time\", \"signal\"?, \"event\"? }}] }}: {}",
+                        i.string.to_string_lossy(),
+                        err
+                    );
+                    AnimationMarkersSchema::default()
+                }
+            },
+            None => AnimationMarkersSchema::default(),
+        };
+        commands.insert_resource(AnimationMarkers(markers));
+
+        let questions = questions::read_questions(&i.string.to_string_lossy());
+        let problems = questions::read_problems(&i.string.to_string_lossy());
+        let seed = level_config
+            .as_ref()
+            .and_then(|p| p.question_seed)
+            .unwrap_or_else(rand::random);
+        if !questions.is_empty() || !problems.is_empty() {
+            println!("{} questions, {} problems, seed {}", questions.len(), problems.len(), seed);
+        }
+        commands.insert_resource(QuestionBank::new(questions, problems, seed));
+
+        commands.insert_resource(CurrentLevelConfig(level_config.unwrap_or_default()));
+
         commands.spawn((
             GltfFileMarker,
             TransitionMarker::new(false, Duration::from_millis(400)),
@@ -55,26 +111,68 @@ pub fn load_gltf_file(
 pub fn spawn_loaded_gltf_scene(
     mut commands: Commands,
     loading_scene: Option<ResMut<LoadingSceneInfo>>,
+    level_config: Res<CurrentLevelConfig>,
+    asset: Res<AssetServer>,
     gltf_asset_manager: Res<Assets<Gltf>>,
     _state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut transition: ResMut<LevelTransition>,
+    file_q: Query<Entity, With<GltfFileMarker>>,
+    mut fade_q: Query<(&mut LevelFadeMarker, &mut TransitionMarker)>,
 ) {
     if let Some(mut scene_info) = loading_scene {
         if scene_info.is_loaded {
             return;
         }
+        if asset.get_load_state(scene_info.handle.id()) == Some(LoadState::Failed) {
+            // back to the menu, same as a file without scenes
+            println!("{}: the entry file failed to load", scene_info.name);
+            commands.remove_resource::<LoadingSceneInfo>();
+            for entity in file_q.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            level_transition::cancel_transition(&mut transition, &mut fade_q);
+            return;
+        }
         if let Some(gltf_asset) = gltf_asset_manager.get(&scene_info.handle) {
             // for (k,v) in gltf.named_animations.iter() {
             //     println!("Action: {}", k);
             // }
+            let scene = match &level_config.0.scene {
+                Some(SceneRef::Index(index)) => gltf_asset.scenes.get(*index).cloned(),
+                Some(SceneRef::Name(name)) => gltf_asset.named_scenes.get(name).cloned(),
+                None => gltf_asset.scenes.first().cloned(),
+            };
+            let scene = scene.or_else(|| {
+                let first = gltf_asset.scenes.first().cloned();
+                if first.is_some() {
+                    println!("{}: no scene {:?}, the first one is used", scene_info.name, level_config.0.scene);
+                }
+                first
+            });
+            let Some(scene) = scene else {
+                // back to the menu, a level being switched to fades it in again
+                println!("{}: the entry file has no scenes", scene_info.name);
+                commands.remove_resource::<LoadingSceneInfo>();
+                for entity in file_q.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                level_transition::cancel_transition(&mut transition, &mut fade_q);
+                return;
+            };
             commands.spawn((
                 MainSceneMarker,
                 SceneBundle {
-                    scene: gltf_asset.scenes[0].clone(),
+                    scene,
                     ..Default::default()
                 },
                 Name::new("Main level scene"),
             ));
+            for section in level_config.0.sections.iter().filter(|p| p.preload) {
+                if let Some(scene) = sections::section_scene(section, gltf_asset, &asset, &scene_info.name) {
+                    commands.spawn(sections::section_bundle(&section.name, scene));
+                }
+            }
             scene_info.is_loaded = true;
             // commands.remove_resource::<SceneTempRes>();
 
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::AssetEvent,
+    gltf::Gltf,
+    prelude::{
+        in_state, Added, Commands, EventReader, IntoSystemConfigs, Plugin, Quat, Query, Res, ResMut,
+        Resource, Transform, Update, Vec3, With, Without, World,
+    },
+};
+
+use crate::{
+    lib::tools::{
+        markers::{PlayerCameraContainerMarker, PlayerParentMarker},
+        resources::{Inventory, LevelTransition, LoadingSceneInfo, PendingLevel, Score},
+    },
+    GameState,
+};
+
+use super::level_transition;
+
+/// Short enough not to get in the way, long enough to hide the menu the reload goes through
+const RELOAD_FADE: Duration = Duration::from_millis(150);
+
+/// Respawns the level once its glTF is re-exported, only with `--features dev`.
+/// Textures are swapped in place by bevy itself, the level stays as it is for those
+pub struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn name(&self) -> &str {
+        "For reloading the level when its files change"
+    }
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<HotReload>().add_systems(
+            Update,
+            (restore_player_pose, watch_level_changes)
+                .chain()
+                .run_if(in_state(GameState::Game)),
+        );
+    }
+}
+
+/// Where player stood and looked before the reload
+#[derive(Resource, Default)]
+pub struct HotReload {
+    pub pose: Option<(Vec3, Quat)>,
+}
+
+pub fn watch_level_changes(
+    mut gltf_ev: EventReader<AssetEvent<Gltf>>,
+    scene_info: Option<Res<LoadingSceneInfo>>,
+    player_q: Query<&Transform, With<PlayerParentMarker>>,
+    camera_q: Query<&Transform, (With<PlayerCameraContainerMarker>, Without<PlayerParentMarker>)>,
+    mut hot_reload: ResMut<HotReload>,
+    mut transition: ResMut<LevelTransition>,
+    inventory: Res<Inventory>,
+    score: Res<Score>,
+    mut commands: Commands,
+) {
+    let Some(scene_info) = scene_info else {
+        return;
+    };
+    let changed = gltf_ev.read().any(|p| match p {
+        AssetEvent::Modified { id } => *id == scene_info.handle.id(),
+        _ => false,
+    });
+    if !changed || transition.pending.is_some() {
+        return;
+    }
+
+    println!("{} changed, reloading", scene_info.name);
+    if let (Ok(player), Ok(camera)) = (player_q.get_single(), camera_q.get_single()) {
+        hot_reload.pose = Some((player.translation, camera.rotation));
+    }
+    // same way as action:load_level goes, with a quicker fade
+    transition.pending = Some(PendingLevel {
+        level: scene_info.name.clone(),
+        inventory: Some(inventory.items.clone()),
+        score: Some(*score),
+        requested: false,
+    });
+    commands.add(|world: &mut World| level_transition::start_fade(world, RELOAD_FADE));
+}
+
+/// The new player shows up a frame after the level is in, the pose only goes on it
+pub fn restore_player_pose(
+    mut player_q: Query<&mut Transform, Added<PlayerParentMarker>>,
+    mut camera_q: Query<&mut Transform, (With<PlayerCameraContainerMarker>, Without<PlayerParentMarker>)>,
+    mut hot_reload: ResMut<HotReload>,
+) {
+    let Some((translation, rotation)) = hot_reload.pose else {
+        return;
+    };
+    let (Ok(mut player), Ok(mut camera)) = (player_q.get_single_mut(), camera_q.get_single_mut()) else {
+        return;
+    };
+    player.translation = translation;
+    camera.rotation = rotation;
+    hot_reload.pose = None;
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::{
+        BackgroundColor, Color, Commands, Component, DespawnRecursiveExt, Entity, EventWriter,
+        Name, NextState, NodeBundle, Query, Res, ResMut, State, World,
+    },
+    time::Time,
+    ui::{PositionType, Style, Val, ZIndex},
+};
+
+use crate::{
+    lib::tools::{
+        events::LoadLevel,
+        resources::{Inventory, LevelStartTime, LevelTransition, Score},
+        transition::TransitionMarker,
+    },
+    GameState,
+};
+
+/// Black screen between two levels, lives through the main menu state
+#[derive(Component)]
+pub struct LevelFadeMarker {
+    pub fading_in: bool,
+}
+
+/// Covers the screen, once it is black the level is swapped
+pub fn start_fade(world: &mut World, duration: Duration) {
+    world.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..Default::default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.).into(),
+            z_index: ZIndex::Global(100),
+            ..Default::default()
+        },
+        LevelFadeMarker { fading_in: false },
+        TransitionMarker::new(true, duration.max(Duration::from_millis(1))),
+        Name::new("Level fade"),
+    ));
+}
+
+pub fn drive_level_fade(
+    mut commands: Commands,
+    mut fade_q: Query<(Entity, &LevelFadeMarker, &mut TransitionMarker, &mut BackgroundColor)>,
+    mut transition: ResMut<LevelTransition>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut load_level_ev: EventWriter<LoadLevel>,
+    time: Res<Time>,
+) {
+    for (entity, fade, mut timer, mut color) in fade_q.iter_mut() {
+        let finished = timer.tick(time.delta()) || timer.timer.finished();
+        let p = timer.ease_in_out().unwrap();
+        color.0 = Color::rgba(0., 0., 0., if fade.fading_in { 1. - p } else { p });
+
+        if finished {
+            if fade.fading_in {
+                commands.entity(entity).despawn_recursive();
+            } else if *state.get() == GameState::Game && transition.pending.is_some() {
+                next_state.0 = Some(GameState::MainMenu);
+            }
+        }
+    }
+
+    // menu is hidden behind the fade, the next level is loaded right away
+    if *state.get() == GameState::MainMenu {
+        if let Some(pending) = transition.pending.as_mut().filter(|p| !p.requested) {
+            pending.requested = true;
+            load_level_ev.send(LoadLevel {
+                string: pending.level.clone().into(),
+            });
+        }
+    }
+}
+
+/// Gives back what was carried over and fades the new level in
+pub fn finish_level_transition(
+    mut fade_q: Query<(&mut LevelFadeMarker, &mut TransitionMarker)>,
+    mut transition: ResMut<LevelTransition>,
+    mut inventory: ResMut<Inventory>,
+    mut score: ResMut<Score>,
+) {
+    let Some(pending) = transition.pending.take() else {
+        return;
+    };
+    if let Some(items) = pending.inventory {
+        inventory.items = items;
+    }
+    if let Some(carried) = pending.score {
+        *score = carried;
+    }
+    for (mut fade, mut timer) in fade_q.iter_mut() {
+        fade.fading_in = true;
+        timer.reset();
+    }
+}
+
+/// Level could not be loaded, the menu behind the fade is shown again
+pub fn cancel_transition(
+    transition: &mut LevelTransition,
+    fade_q: &mut Query<(&mut LevelFadeMarker, &mut TransitionMarker)>,
+) {
+    transition.pending = None;
+    for (mut fade, mut timer) in fade_q.iter_mut() {
+        fade.fading_in = true;
+        timer.reset();
+    }
+}
+
+pub fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+/// Best time in the progress is counted from here
+pub fn start_level_clock(mut start: ResMut<LevelStartTime>, time: Res<Time>) {
+    start.0 = time.elapsed();
+}
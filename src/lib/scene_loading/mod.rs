@@ -1,6 +1,7 @@
 use self::attachements::{update_timer, prepare_rapier, attach_collider, attach_collision_groups, gltf_adjust_light};
-use self::changing_cusom_properties::gltf_load_extras;
+use self::changing_cusom_properties::{apply_prefab_overrides, gltf_load_extras};
 use self::gltf_handling::{load_gltf_file, spawn_loaded_gltf_scene};
+use self::level_transition::{drive_level_fade, finish_level_transition, reset_score, start_level_clock};
 use self::unload::unload;
 use super::broadcast;
 use crate::GameState;
@@ -18,6 +19,10 @@ pub mod changing_cusom_properties;
 pub mod components;
 pub mod custom_properties;
 pub mod gltf_handling;
+#[cfg(feature = "dev")]
+pub mod hot_reload;
+pub mod level_transition;
+pub mod sections;
 pub mod unload;
 
 pub struct SceneLoaderPlugin;
@@ -43,14 +48,19 @@ impl Plugin for SceneLoaderPlugin {
                     update_timer,
                     // gltf_load_colliders,
                     prepare_rapier,
-                    (gltf_load_extras, (attach_collider, attach_collision_groups)).chain(),
+                    (apply_prefab_overrides, gltf_load_extras, (attach_collider, attach_collision_groups)).chain(),
                     gltf_adjust_light,
                 )
                     .distributive_run_if(in_state(GameState::Game)),
             ).add_systems(
                 OnExit(GameState::Game), 
-                unload
-            );
+                (unload, reset_score)
+            )
+            .add_systems(OnEnter(GameState::Game), (finish_level_transition, start_level_clock))
+            .add_systems(Update, drive_level_fade);
+
+        #[cfg(feature = "dev")]
+        app.add_plugins(hot_reload::HotReloadPlugin);
     }
 }
 
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use bevy::{
+    gltf::Gltf,
+    hierarchy::despawn_with_children_recursive,
+    prelude::{warn, AssetServer, Assets, Bundle, Entity, Handle, Name, World},
+    scene::{Scene, SceneBundle},
+};
+
+use crate::lib::tools::{
+    config::SectionSchema,
+    resources::{CurrentLevelConfig, LoadingSceneInfo},
+};
+
+use super::components::{SectionMarker, SubSceneMarker};
+
+/// Scene a section is made of, `None` if config.json points to nothing
+pub fn section_scene(
+    section: &SectionSchema,
+    gltf: &Gltf,
+    asset: &AssetServer,
+    level: &str,
+) -> Option<Handle<Scene>> {
+    if let Some(name) = &section.scene {
+        let scene = gltf.named_scenes.get(name).cloned();
+        if scene.is_none() {
+            warn!("Section {}: no scene {} in the level glTF", section.name, name);
+        }
+        scene
+    } else if let Some(file) = &section.file {
+        let path = PathBuf::from("levels").join(level).join(file);
+        Some(asset.load(format!("{}#Scene0", path.display())))
+    } else {
+        warn!("Section {} has neither scene nor file", section.name);
+        None
+    }
+}
+
+pub fn section_bundle(name: &str, scene: Handle<Scene>) -> impl Bundle {
+    (
+        SceneBundle {
+            scene,
+            ..Default::default()
+        },
+        SectionMarker(name.to_owned()),
+        SubSceneMarker,
+        Name::new(format!("Section {}", name)),
+    )
+}
+
+fn loaded_section(world: &mut World, name: &str) -> Option<Entity> {
+    world
+        .query::<(Entity, &SectionMarker)>()
+        .iter(world)
+        .find(|p| p.1 .0 == name)
+        .map(|p| p.0)
+}
+
+/// Spawns the section, its nodes go through `gltf_load_extras` as any other
+pub fn load_section(world: &mut World, name: &str) {
+    if loaded_section(world, name).is_some() {
+        return;
+    }
+    let Some(section) = world
+        .resource::<CurrentLevelConfig>()
+        .0
+        .sections
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+    else {
+        warn!("No section {} in config.json", name);
+        return;
+    };
+
+    let scene_info = world.resource::<LoadingSceneInfo>();
+    let Some(gltf) = world.resource::<Assets<Gltf>>().get(&scene_info.handle) else {
+        return;
+    };
+    let Some(scene) = section_scene(
+        &section,
+        gltf,
+        world.resource::<AssetServer>(),
+        &scene_info.name,
+    ) else {
+        return;
+    };
+    println!("Loading section {}", name);
+    world.spawn(section_bundle(name, scene));
+}
+
+/// Despawns the section together with its actions and colliders
+pub fn unload_section(world: &mut World, name: &str) {
+    if let Some(root) = loaded_section(world, name) {
+        println!("Unloading section {}", name);
+        despawn_with_children_recursive(world, root);
+    }
+}
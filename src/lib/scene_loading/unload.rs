@@ -1,12 +1,12 @@
 use bevy::prelude::{Query, With, Or, Entity, Commands, DespawnRecursiveExt, Res, State};
 
-use crate::{lib::tools::markers::PlayerParentMarker, PlayerState};
+use crate::{lib::tools::markers::{LevelUiMarker, PlayerBorderPostProcessCamera, PlayerParentMarker, PostProcessMeshEntityMarker}, PlayerState};
 
-use super::components::{GltfFileMarker, MainSceneMarker};
+use super::components::{GltfFileMarker, MainSceneMarker, SubSceneMarker};
 
 pub fn unload(
     mut commands: Commands,
-    query: Query<Entity, Or<(With<GltfFileMarker>,With<MainSceneMarker>, With<PlayerParentMarker>)>>,
+    query: Query<Entity, Or<(With<GltfFileMarker>,With<MainSceneMarker>, With<SubSceneMarker>, With<PlayerParentMarker>, With<PlayerBorderPostProcessCamera>, With<PostProcessMeshEntityMarker>, With<LevelUiMarker>)>>,
 ) {
     
     for i in query.iter() {
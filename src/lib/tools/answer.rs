@@ -0,0 +1,457 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// How forgiving the comparison of typed answers is
+#[derive(Default, Clone, Copy)]
+pub struct AnswerTolerance {
+    /// "10 м" equals "10м"
+    pub ignore_whitespace: bool,
+    /// "м/с." equals "мс"
+    pub ignore_punctuation: bool,
+    /// Answers are numbers with an optional unit, see `check_number`
+    pub numeric: bool,
+    /// Fraction of the expected value the given one may be off by
+    pub relative: f64,
+    /// Or that much in the expected unit
+    pub absolute: f64,
+    /// "0,5" is wrong where "0.5 A" is expected
+    pub unit_required: bool,
+}
+
+impl AnswerTolerance {
+    pub fn from_extras(main: &serde_json::map::Map<String, serde_json::Value>) -> AnswerTolerance {
+        let flag = |name: &str| main.get(name).and_then(|p| p.as_bool()).unwrap_or(false);
+        let number = |name: &str, default: f64| main.get(name).and_then(|p| p.as_f64()).unwrap_or(default).abs();
+        AnswerTolerance {
+            ignore_whitespace: flag("#answer_ignore_whitespace"),
+            ignore_punctuation: flag("#answer_ignore_punctuation"),
+            numeric: main.get("#answer_type").and_then(|p| p.as_str()) == Some("number"),
+            relative: number("#answer_tolerance", 0.01),
+            absolute: number("#answer_tolerance_absolute", 0.),
+            unit_required: flag("#answer_unit_required"),
+        }
+    }
+}
+
+/// Extra signals telling what was wrong with an answer
+#[derive(Default, Clone, Copy)]
+pub struct AnswerFeedback {
+    pub wrong_value: Option<u64>,
+    pub wrong_unit: Option<u64>,
+    pub unreadable: Option<u64>,
+}
+
+impl AnswerFeedback {
+    pub fn from_extras(main: &serde_json::map::Map<String, serde_json::Value>) -> AnswerFeedback {
+        let signal = |name: &str| main.get(name).and_then(|p| p.as_u64());
+        AnswerFeedback {
+            wrong_value: signal("#answer_wrong_value_signal"),
+            wrong_unit: signal("#answer_wrong_unit_signal"),
+            unreadable: signal("#answer_unreadable_signal"),
+        }
+    }
+
+    pub fn signal(&self, check: AnswerCheck) -> Option<u64> {
+        match check {
+            AnswerCheck::WrongValue => self.wrong_value,
+            AnswerCheck::WrongUnit => self.wrong_unit,
+            AnswerCheck::Unreadable => self.unreadable,
+            AnswerCheck::Right => None,
+        }
+    }
+}
+
+/// NFKC, lower case, one kind of apostrophe, and whitespace trimmed and collapsed
+/// (or dropped, as `tolerance` says)
+pub fn normalize_answer(text: &str, tolerance: AnswerTolerance) -> String {
+    let folded = text
+        .nfkc()
+        .flat_map(|p| p.to_lowercase())
+        .map(|p| match p {
+            // Ukrainian words are typed with any of these
+            'ʼ' | '’' | '`' | '‘' | '′' => '\'',
+            p => p,
+        })
+        .filter(|p| !tolerance.ignore_punctuation || !is_punctuation(*p))
+        .collect::<String>();
+
+    if tolerance.ignore_whitespace {
+        folded.chars().filter(|p| !p.is_whitespace()).collect()
+    } else {
+        folded.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '«' | '»' | '„' | '“' | '”' | '–' | '—' | '…' | '·')
+}
+
+pub fn answers_match(given: &str, expected: &str, tolerance: AnswerTolerance) -> bool {
+    normalize_answer(given, tolerance) == normalize_answer(expected, tolerance)
+}
+
+/// What was wrong with an answer, for feedback
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnswerCheck {
+    Right,
+    /// Text that differs, or a number too far off
+    WrongValue,
+    /// Number in a unit that can't be turned into the expected one, or without a required unit
+    WrongUnit,
+    /// Not a number where one is expected
+    Unreadable,
+}
+
+pub fn check_answer(given: &str, expected: &str, tolerance: AnswerTolerance) -> AnswerCheck {
+    if tolerance.numeric {
+        check_number(given, expected, tolerance)
+    } else if answers_match(given, expected, tolerance) {
+        AnswerCheck::Right
+    } else {
+        AnswerCheck::WrongValue
+    }
+}
+
+/// SI prefixes, latin and Ukrainian. Longer ones go first so "da" and "мк" are not read as "d" and "м"
+const SI_PREFIXES: [(&str, f64); 29] = [
+    ("da", 1e1),
+    ("мк", 1e-6),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("Т", 1e12),
+    ("Г", 1e9),
+    ("М", 1e6),
+    ("к", 1e3),
+    ("д", 1e-1),
+    ("с", 1e-2),
+    ("м", 1e-3),
+    ("н", 1e-9),
+    ("п", 1e-12),
+];
+
+/// "0,5 A", "5e-1 A", "5·10^-1 A", "5×10⁻¹ A" -> (0.5, "A").
+/// The unit is whatever follows the number, possibly empty
+pub fn parse_quantity(text: &str) -> Option<(f64, String)> {
+    let text = text
+        .nfkc()
+        .map(|p| match p {
+            ',' => '.',
+            // NFKC turns a superscript minus into the minus sign
+            '−' | '–' | '—' => '-',
+            'μ' => 'µ',
+            p => p,
+        })
+        .collect::<String>();
+    let chars = text.trim().chars().collect::<Vec<_>>();
+    let mut at = 0;
+
+    let digits = |at: &mut usize| {
+        let start = *at;
+        while chars.get(*at).is_some_and(|p| p.is_ascii_digit()) {
+            *at += 1;
+        }
+        *at > start
+    };
+    let sign = |at: &mut usize| {
+        if matches!(chars.get(*at), Some('+' | '-')) {
+            *at += 1;
+        }
+    };
+    let skip_spaces = |at: &mut usize| {
+        while chars.get(*at).is_some_and(|p| p.is_whitespace()) {
+            *at += 1;
+        }
+    };
+
+    sign(&mut at);
+    let whole = digits(&mut at);
+    let mut fraction = false;
+    if chars.get(at) == Some(&'.') {
+        at += 1;
+        fraction = digits(&mut at);
+    }
+    if !whole && !fraction {
+        return None;
+    }
+    let mut value = chars[..at].iter().collect::<String>().parse::<f64>().ok()?;
+
+    // exponent, only taken if it is complete, otherwise it is a part of the unit
+    let mut probe = at;
+    if matches!(chars.get(probe), Some('e' | 'E')) {
+        probe += 1;
+        let start = probe;
+        sign(&mut probe);
+        if digits(&mut probe) {
+            value *= 10f64.powi(chars[start..probe].iter().collect::<String>().parse().ok()?);
+            at = probe;
+        }
+    } else {
+        skip_spaces(&mut probe);
+        if matches!(chars.get(probe), Some('*' | '·' | '×' | 'x' | '⋅')) {
+            probe += 1;
+            skip_spaces(&mut probe);
+            if chars.get(probe) == Some(&'1') && chars.get(probe + 1) == Some(&'0') {
+                probe += 2;
+                if chars.get(probe) == Some(&'^') {
+                    probe += 1;
+                }
+                let start = probe;
+                sign(&mut probe);
+                if digits(&mut probe) {
+                    value *= 10f64.powi(chars[start..probe].iter().collect::<String>().parse().ok()?);
+                    at = probe;
+                }
+            }
+        }
+    }
+
+    let unit = chars[at..].iter().collect::<String>().trim().to_owned();
+    Some((value, unit))
+}
+
+/// Ukrainian unit symbols (and spelled out ones) and the SI symbols they stand for
+const UNIT_NAMES: [(&str, &str); 25] = [
+    ("м", "m"),
+    ("г", "g"),
+    ("с", "s"),
+    ("А", "A"),
+    ("К", "K"),
+    ("моль", "mol"),
+    ("кд", "cd"),
+    ("Н", "N"),
+    ("Дж", "J"),
+    ("Вт", "W"),
+    ("Па", "Pa"),
+    ("Гц", "Hz"),
+    ("Кл", "C"),
+    ("В", "V"),
+    ("Ом", "Ω"),
+    ("Ohm", "Ω"),
+    ("ohm", "Ω"),
+    ("Ф", "F"),
+    ("Тл", "T"),
+    ("Гн", "H"),
+    ("Вб", "Wb"),
+    ("л", "L"),
+    ("хв", "min"),
+    ("год", "h"),
+    ("еВ", "eV"),
+];
+
+/// Cyrillic letters that look like latin ones, a unit typed on the Ukrainian layout
+/// is often a mix of both
+fn fold_lookalike(c: char) -> char {
+    match c {
+        'А' => 'A',
+        'В' => 'B',
+        'С' => 'C',
+        'Е' => 'E',
+        'Н' => 'H',
+        'І' => 'I',
+        'К' => 'K',
+        'М' => 'M',
+        'О' => 'O',
+        'Р' => 'P',
+        'Т' => 'T',
+        'Х' => 'X',
+        'а' => 'a',
+        'е' => 'e',
+        'і' => 'i',
+        'о' => 'o',
+        'р' => 'p',
+        'с' => 'c',
+        'х' => 'x',
+        c => c,
+    }
+}
+
+/// "м/с" -> "m/s", "А" -> "A". Each symbol between / · * is looked up in `UNIT_NAMES`,
+/// otherwise its look-alike letters are folded. Powers stay as they are
+fn canonical_unit(unit: &str) -> String {
+    let symbol = |symbol: &str| match UNIT_NAMES.iter().find(|p| p.0 == symbol) {
+        Some(p) => p.1.to_owned(),
+        None => symbol.chars().map(fold_lookalike).collect::<String>(),
+    };
+
+    let mut out = String::new();
+    let mut token = String::new();
+    for c in unit.chars() {
+        if c.is_alphabetic() {
+            token.push(c);
+            continue;
+        }
+        if !token.is_empty() {
+            out += &symbol(&token);
+            token.clear();
+        }
+        out.push(match c {
+            '⋅' | '×' | '*' => '·',
+            c => c,
+        });
+    }
+    if !token.is_empty() {
+        out += &symbol(&token);
+    }
+    out
+}
+
+/// How many `expected` units one `given` unit is, "mA" is 0.001 of "A".
+/// Exact match first, then the same unit under different prefixes.
+/// Ukrainian symbols and Cyrillic look-alikes count as the latin ones, "мА" is "mA"
+pub fn unit_factor(given: &str, expected: &str) -> Option<f64> {
+    if canonical_unit(given) == canonical_unit(expected) {
+        return Some(1.);
+    }
+    let prefixes = std::iter::once(("", 1.)).chain(SI_PREFIXES.iter().copied());
+    for (given_prefix, given_factor) in prefixes.clone() {
+        let Some(base) = given.strip_prefix(given_prefix).filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let base = canonical_unit(base);
+        for (expected_prefix, expected_factor) in prefixes.clone() {
+            let Some(expected_base) = expected.strip_prefix(expected_prefix).filter(|p| !p.is_empty()) else {
+                continue;
+            };
+            if canonical_unit(expected_base) == base {
+                return Some(given_factor / expected_factor);
+            }
+        }
+    }
+    None
+}
+
+/// `expected` is written the same way as answers are, e.g. "0.5 A".
+/// Without a unit the given number is taken to be in the expected unit
+pub fn check_number(given: &str, expected: &str, tolerance: AnswerTolerance) -> AnswerCheck {
+    let Some((expected_value, expected_unit)) = parse_quantity(expected) else {
+        println!("Expected answer \"{}\" is not a number", expected);
+        return AnswerCheck::WrongValue;
+    };
+    let Some((value, unit)) = parse_quantity(given) else {
+        return AnswerCheck::Unreadable;
+    };
+
+    let value = if unit.is_empty() {
+        if tolerance.unit_required && !expected_unit.is_empty() {
+            return AnswerCheck::WrongUnit;
+        }
+        value
+    } else {
+        match unit_factor(&unit, &expected_unit) {
+            Some(factor) => value * factor,
+            None => return AnswerCheck::WrongUnit,
+        }
+    };
+
+    let difference = (value - expected_value).abs();
+    if difference <= tolerance.absolute || difference <= tolerance.relative * expected_value.abs() {
+        AnswerCheck::Right
+    } else {
+        AnswerCheck::WrongValue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric() -> AnswerTolerance {
+        AnswerTolerance {
+            numeric: true,
+            relative: 0.01,
+            ..Default::default()
+        }
+    }
+
+    fn quantity(text: &str) -> (f64, String) {
+        let (value, unit) = parse_quantity(text).unwrap();
+        assert!((value - 0.5).abs() < 1e-9, "{} is read as {}", text, value);
+        (value, unit)
+    }
+
+    #[test]
+    fn parses_quantities() {
+        assert_eq!(quantity("0,5 A").1, "A");
+        assert_eq!(quantity("0.5A").1, "A");
+        assert_eq!(quantity("5e-1 A").1, "A");
+        assert_eq!(quantity("5·10^-1 A").1, "A");
+        assert_eq!(quantity("5×10⁻¹ A").1, "A");
+        assert_eq!(quantity("0,5").1, "");
+        assert_eq!(parse_quantity("500 мА"), Some((500., "мА".to_owned())));
+        assert_eq!(parse_quantity("-3.5 m/s"), Some((-3.5, "m/s".to_owned())));
+        assert_eq!(parse_quantity("half an ampere"), None);
+        assert_eq!(parse_quantity(""), None);
+    }
+
+    #[test]
+    fn converts_units() {
+        assert_eq!(unit_factor("A", "A"), Some(1.));
+        assert_eq!(unit_factor("mA", "A"), Some(1e-3));
+        assert_eq!(unit_factor("km", "m"), Some(1e3));
+        assert_eq!(unit_factor("m", "km"), Some(1e-3));
+        assert_eq!(unit_factor("s", "A"), None);
+        assert_eq!(unit_factor("mA", "V"), None);
+    }
+
+    #[test]
+    fn converts_ukrainian_units() {
+        // Cyrillic А, latin A
+        assert_eq!(unit_factor("\u{410}", "A"), Some(1.));
+        assert_eq!(unit_factor("мА", "A"), Some(1e-3));
+        assert_eq!(unit_factor("мА", "mA"), Some(1.));
+        assert_eq!(unit_factor("В", "V"), Some(1.));
+        assert_eq!(unit_factor("кВт", "kW"), Some(1.));
+        assert_eq!(unit_factor("МВт", "W"), Some(1e6));
+        assert_eq!(unit_factor("Ом", "Ω"), Some(1.));
+        assert_eq!(unit_factor("кОм", "Ω"), Some(1e3));
+        assert_eq!(unit_factor("м/с", "m/s"), Some(1.));
+        assert_eq!(unit_factor("км/год", "km/h"), Some(1.));
+        assert_eq!(unit_factor("мм", "m"), Some(1e-3));
+        assert_eq!(unit_factor("с", "s"), Some(1.));
+        assert_eq!(unit_factor("Н", "N"), Some(1.));
+        assert_eq!(unit_factor("кг", "kg"), Some(1.));
+        assert_eq!(unit_factor("г", "kg"), Some(1e-3));
+    }
+
+    #[test]
+    fn checks_documented_answers() {
+        for given in ["0.5 A", "0,5", "5e-1 A", "5·10^-1 A", "500 mA", "500 мА", "0,5 А", "0.504 A"] {
+            assert_eq!(check_number(given, "0.5 A", numeric()), AnswerCheck::Right, "{}", given);
+        }
+        // expected written on the Ukrainian layout
+        assert_eq!(check_number("0.5 A", "0,5 А", numeric()), AnswerCheck::Right);
+        assert_eq!(check_number("0.6 A", "0.5 A", numeric()), AnswerCheck::WrongValue);
+        assert_eq!(check_number("0.5 V", "0.5 A", numeric()), AnswerCheck::WrongUnit);
+        assert_eq!(check_number("half", "0.5 A", numeric()), AnswerCheck::Unreadable);
+
+        let unit_required = AnswerTolerance {
+            unit_required: true,
+            ..numeric()
+        };
+        assert_eq!(check_number("0,5", "0.5 A", unit_required), AnswerCheck::WrongUnit);
+        assert_eq!(check_number("0,5 А", "0.5 A", unit_required), AnswerCheck::Right);
+
+        let absolute = AnswerTolerance {
+            relative: 0.,
+            absolute: 0.1,
+            ..numeric()
+        };
+        assert_eq!(check_number("0.58 A", "0.5 A", absolute), AnswerCheck::Right);
+        assert_eq!(check_number("0.62 A", "0.5 A", absolute), AnswerCheck::WrongValue);
+    }
+}
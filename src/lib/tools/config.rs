@@ -1,8 +1,208 @@
+use std::{env, ops::RangeInclusive, path::PathBuf};
+
+use bevy::utils::{HashMap, HashSet};
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize)]
+use super::level_pack;
+
+/// `version` of config.json this build understands
+pub const LEVEL_VERSIONS: RangeInclusive<u32> = 1..=1;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct LevelSchema {
     pub name: String,
     pub version: u32,
     pub prioritize: u32,
-}
\ No newline at end of file
+    /// What the lecture covers, a paragraph or two
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Estimated minutes to go through
+    #[serde(default)]
+    pub duration: Option<u32>,
+    /// 1 to 5
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    /// What a student should learn, one per line of the detail panel
+    #[serde(default)]
+    pub objectives: Vec<String>,
+    /// "uk", "en", ...
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Image in the level folder shown instead of preview.png
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    /// glTF in the level folder to load, `main.gltf` or `main.glb` by default
+    #[serde(default)]
+    pub entry: Option<String>,
+    /// Scene of the entry file to spawn, the first one by default
+    #[serde(default)]
+    pub scene: Option<SceneRef>,
+    /// Parts of the level loaded and unloaded by `action:section_trigger`
+    #[serde(default)]
+    pub sections: Vec<SectionSchema>,
+    /// Level folder `action:load_level` goes to by default
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Level folders to be completed before this one opens
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Level folders this one opens once completed
+    #[serde(default)]
+    pub unlocks: Vec<String>,
+    /// Same questions in the same order each time, random if not set
+    #[serde(default)]
+    pub question_seed: Option<u64>,
+}
+
+/// `0` or `"Scene name"`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum SceneRef {
+    Index(usize),
+    Name(String),
+}
+
+/// Either `scene` or `file` is set
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SectionSchema {
+    pub name: String,
+    /// Named scene in the entry file
+    #[serde(default)]
+    pub scene: Option<String>,
+    /// glTF in the level folder, its first scene is used
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Loaded together with the level
+    #[serde(default)]
+    pub preload: bool,
+}
+
+/// One entry of `animation_markers.json`, which lies next to the entry file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnimationMarkerSchema {
+    pub name: String,
+    /// Seconds from the start of the clip
+    pub time: f32,
+    #[serde(default)]
+    pub signal: Option<u64>,
+    /// Sent as `CustomEvent` with that name
+    #[serde(default)]
+    pub event: Option<String>,
+}
+
+impl LevelSchema {
+    /// Why this build can't open the level, if it can't
+    pub fn version_problem(&self) -> Option<String> {
+        if self.version > *LEVEL_VERSIONS.end() {
+            Some(format!(
+                "@level.version_too_new:{}|{}",
+                self.version,
+                LEVEL_VERSIONS.end()
+            ))
+        } else if self.version < *LEVEL_VERSIONS.start() {
+            Some(format!(
+                "@level.version_too_old:{}|{}",
+                self.version,
+                LEVEL_VERSIONS.start()
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Clip name -> its markers
+pub type AnimationMarkersSchema = HashMap<String, Vec<AnimationMarkerSchema>>;
+
+/// Where `assets` folder is, same lookup as bevy does
+pub fn assets_path() -> PathBuf {
+    let path = if let Ok(manifest_dir) = env::var("BEVY_ASSET_ROOT") {
+        PathBuf::from(manifest_dir)
+    } else if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        PathBuf::from(manifest_dir)
+    } else {
+        env::current_exe()
+            .map(|path| {
+                path.parent()
+                    .map(|exe_parent_path| exe_parent_path.to_owned())
+                    .unwrap()
+            })
+            .unwrap()
+    };
+    path.join("assets")
+}
+
+/// `config.json` of a level folder or pack, if it has one
+pub fn read_level_config(folder: &str) -> Option<LevelSchema> {
+    let file = level_pack::read_level_file(folder, "config.json")?;
+    Some(serde_json::from_slice::<LevelSchema>(&file).expect("config.json is broken"))
+}
+
+/// Level folder names, `levels/<name>.zip` packs included as `<name>`
+pub fn level_folders() -> Vec<String> {
+    let Ok(dir) = assets_path().join("levels").read_dir() else {
+        return vec![];
+    };
+    let mut folders = dir
+        .filter_map(|p| p.ok())
+        .filter_map(|p| {
+            let path = p.path();
+            if path.is_dir() {
+                Some(p.file_name().to_string_lossy().into_owned())
+            } else if path.extension().is_some_and(|p| p == "zip") {
+                path.file_stem().map(|p| p.to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+/// Entry file of a level folder or pack, `None` if it is not a level
+pub fn level_entry(folder: &str, config: Option<&LevelSchema>) -> Option<String> {
+    if let Some(entry) = config.and_then(|p| p.entry.clone()) {
+        if level_pack::level_has_file(folder, &entry) {
+            return Some(entry);
+        }
+        println!("{}: entry {} of config.json is not found", folder, entry);
+        return None;
+    }
+    ["main.gltf", "main.glb"]
+        .into_iter()
+        .find(|p| level_pack::level_has_file(folder, p))
+        .map(|p| p.to_owned())
+}
+
+/// Every level folder with its config
+pub fn read_levels() -> Vec<(String, LevelSchema)> {
+    level_folders()
+        .into_iter()
+        .filter_map(|folder| read_level_config(&folder).map(|config| (folder, config)))
+        .collect()
+}
+
+/// Open once everything it `requires` is completed and, if some levels `unlock` it, one of those is
+pub fn is_level_unlocked(
+    folder: &str,
+    levels: &[(String, LevelSchema)],
+    completed: &HashSet<String>,
+) -> bool {
+    let Some((_, config)) = levels.iter().find(|p| p.0 == folder) else {
+        return true;
+    };
+    if !config.requires.iter().all(|p| completed.contains(p)) {
+        return false;
+    }
+    let mut unlocked_by = levels
+        .iter()
+        .filter(|p| p.1.unlocks.iter().any(|p| p == folder))
+        .peekable();
+    unlocked_by.peek().is_none() || unlocked_by.any(|p| completed.contains(&p.0))
+}
@@ -0,0 +1,199 @@
+use bevy::utils::HashMap;
+
+/// Evaluates answer formulas of problems.json: numbers, variables, + - * / ^, parentheses,
+/// sqrt, abs, sin, cos, tan (radians), asin, acos, atan, ln, log (base 10), exp, pi
+pub fn evaluate(formula: &str, variables: &HashMap<String, f64>) -> Result<f64, String> {
+    let mut parser = Parser {
+        chars: formula.chars().collect(),
+        at: 0,
+        variables,
+    };
+    let value = parser.sum()?;
+    parser.skip_spaces();
+    if parser.at < parser.chars.len() {
+        return Err(format!("unexpected \"{}\" at {}", parser.chars[parser.at], parser.at));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    at: usize,
+    variables: &'a HashMap<String, f64>,
+}
+
+impl Parser<'_> {
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.at).is_some_and(|p| p.is_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+    /// Next non-space char, taken if it is one of `ops`
+    fn take(&mut self, ops: &[char]) -> Option<char> {
+        self.skip_spaces();
+        let c = *self.chars.get(self.at)?;
+        ops.contains(&c).then(|| {
+            self.at += 1;
+            c
+        })
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        while let Some(op) = self.take(&['+', '-']) {
+            let rhs = self.product()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.take(&['*', '/', '·', '×']) {
+            let rhs = self.unary()?;
+            value = if op == '/' { value / rhs } else { value * rhs };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.take(&['-', '+']) {
+            Some('-') => Ok(-self.unary()?),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    /// Right associative, -2^2 is -4
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        if self.take(&['^']).is_some() {
+            let exponent = self.unary()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        self.skip_spaces();
+        let Some(&c) = self.chars.get(self.at) else {
+            return Err("formula ends too early".into());
+        };
+
+        if c == '(' {
+            self.at += 1;
+            let value = self.sum()?;
+            return match self.take(&[')']) {
+                Some(_) => Ok(value),
+                None => Err(format!("missing ) at {}", self.at)),
+            };
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let start = self.at;
+            while self.chars.get(self.at).is_some_and(|p| p.is_ascii_digit() || *p == '.') {
+                self.at += 1;
+            }
+            // 1e-3
+            if matches!(self.chars.get(self.at), Some('e' | 'E'))
+                && self.chars.get(self.at + 1).is_some_and(|p| p.is_ascii_digit() || *p == '-' || *p == '+')
+            {
+                self.at += 2;
+                while self.chars.get(self.at).is_some_and(|p| p.is_ascii_digit()) {
+                    self.at += 1;
+                }
+            }
+            let number = self.chars[start..self.at].iter().collect::<String>();
+            return number.parse::<f64>().map_err(|_| format!("bad number \"{}\"", number));
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = self.at;
+            while self.chars.get(self.at).is_some_and(|p| p.is_alphanumeric() || *p == '_') {
+                self.at += 1;
+            }
+            let name = self.chars[start..self.at].iter().collect::<String>();
+
+            if self.take(&['(']).is_some() {
+                let arg = self.sum()?;
+                if self.take(&[')']).is_none() {
+                    return Err(format!("missing ) after {}(", name));
+                }
+                return match name.as_str() {
+                    "sqrt" => Ok(arg.sqrt()),
+                    "abs" => Ok(arg.abs()),
+                    "sin" => Ok(arg.sin()),
+                    "cos" => Ok(arg.cos()),
+                    "tan" => Ok(arg.tan()),
+                    "asin" => Ok(arg.asin()),
+                    "acos" => Ok(arg.acos()),
+                    "atan" => Ok(arg.atan()),
+                    "ln" => Ok(arg.ln()),
+                    "log" => Ok(arg.log10()),
+                    "exp" => Ok(arg.exp()),
+                    _ => Err(format!("unknown function {}", name)),
+                };
+            }
+
+            return match (self.variables.get(&name), name.as_str()) {
+                (Some(value), _) => Ok(*value),
+                (None, "pi") => Ok(std::f64::consts::PI),
+                _ => Err(format!("unknown variable {}", name)),
+            };
+        }
+
+        Err(format!("unexpected \"{}\" at {}", c, self.at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(formula: &str) -> Result<f64, String> {
+        let variables = [("U".to_owned(), 12.), ("R".to_owned(), 4.)]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        evaluate(formula, &variables)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("2 + 3 * 4"), Ok(14.));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(20.));
+        assert_eq!(eval("10 - 4 - 3"), Ok(3.));
+        assert_eq!(eval("12 / 3 / 2"), Ok(2.));
+        assert_eq!(eval("2^3^2"), Ok(512.));
+        assert_eq!(eval("2 * 3^2"), Ok(18.));
+    }
+
+    #[test]
+    fn signs_and_powers() {
+        assert_eq!(eval("-2^2"), Ok(-4.));
+        assert_eq!(eval("(-2)^2"), Ok(4.));
+        assert_eq!(eval("2^-1"), Ok(0.5));
+        assert_eq!(eval("3 * -2"), Ok(-6.));
+    }
+
+    #[test]
+    fn numbers_variables_functions() {
+        assert_eq!(eval("U / R"), Ok(3.));
+        assert_eq!(eval("U·R"), Ok(48.));
+        assert_eq!(eval("1e-3 * 1000"), Ok(1.));
+        assert_eq!(eval("sqrt(R) + abs(-1)"), Ok(3.));
+        assert_eq!(eval("pi"), Ok(std::f64::consts::PI));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(eval("").is_err());
+        assert!(eval("2 +").is_err());
+        assert!(eval("(1 + 2").is_err());
+        assert!(eval("sqrt(4").is_err());
+        assert!(eval("foo(1)").is_err());
+        assert!(eval("x * 2").is_err());
+        assert!(eval("2 2").is_err());
+        assert!(eval("2 $ 2").is_err());
+    }
+}
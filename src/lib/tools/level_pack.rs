@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    asset::io::{AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder, PathStream, Reader, VecReader},
+    tasks::futures_lite::stream,
+    utils::{BoxedFuture, HashMap},
+};
+use zip::ZipArchive;
+
+use super::config;
+
+/// `assets/levels/<folder>.zip`, a whole level folder in one file.
+/// Files may lie at the root of the archive or in a `<folder>/` directory in it
+pub fn pack_path(folder: &str) -> PathBuf {
+    config::assets_path().join("levels").join(format!("{}.zip", folder))
+}
+
+/// A loose folder wins over a pack with the same name
+pub fn is_pack(folder: &str) -> bool {
+    !config::assets_path().join("levels").join(folder).is_dir() && pack_path(folder).is_file()
+}
+
+fn open(folder: &str) -> Option<ZipArchive<File>> {
+    let file = File::open(pack_path(folder)).ok()?;
+    match ZipArchive::new(file) {
+        Ok(p) => Some(p),
+        Err(err) => {
+            println!("{}.zip is broken: {}", folder, err);
+            None
+        }
+    }
+}
+
+/// Packs opened so far, their central directory is read once and not for every file.
+/// One is opened again once its zip on disk changes
+static PACKS: OnceLock<Mutex<HashMap<String, (Option<SystemTime>, ZipArchive<File>)>>> = OnceLock::new();
+
+/// Runs `f` on the pack of `folder`, opening it if it was not yet
+fn with_pack<T>(folder: &str, f: impl FnOnce(&mut ZipArchive<File>) -> Option<T>) -> Option<T> {
+    let modified = std::fs::metadata(pack_path(folder)).and_then(|p| p.modified()).ok();
+    let mut packs = PACKS.get_or_init(Default::default).lock().unwrap();
+    if packs.get(folder).map_or(true, |p| p.0 != modified) {
+        packs.remove(folder);
+        packs.insert(folder.to_owned(), (modified, open(folder)?));
+    }
+    f(&mut packs.get_mut(folder)?.1)
+}
+
+/// Name of the entry in the archive, with or without the `<folder>/` prefix
+fn entry_name(archive: &ZipArchive<File>, folder: &str, file: &str) -> Option<String> {
+    let prefixed = format!("{}/{}", folder, file);
+    archive
+        .file_names()
+        .find(|p| *p == file || *p == prefixed)
+        .map(|p| p.to_owned())
+}
+
+/// Direct children of `dir` in the pack, relative to the pack root
+fn entries_under(archive: &ZipArchive<File>, folder: &str, dir: &str) -> Vec<String> {
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir.trim_end_matches('/'))
+    };
+    let nested = format!("{}/", folder);
+    let mut children = archive
+        .file_names()
+        .map(|p| p.strip_prefix(&nested).unwrap_or(p))
+        .filter_map(|p| p.strip_prefix(&prefix))
+        .filter_map(|p| p.split('/').next().filter(|p| !p.is_empty()))
+        .map(|p| format!("{}{}", prefix, p))
+        .collect::<Vec<_>>();
+    children.sort();
+    children.dedup();
+    children
+}
+
+pub fn read_pack_file(folder: &str, file: &str) -> Option<Vec<u8>> {
+    with_pack(folder, |archive| {
+        let name = entry_name(archive, folder, file)?;
+        let mut entry = archive.by_name(&name).ok()?;
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes).ok()?;
+        Some(bytes)
+    })
+}
+
+pub fn pack_has_file(folder: &str, file: &str) -> bool {
+    with_pack(folder, |archive| entry_name(archive, folder, file)).is_some()
+}
+
+/// A file of a level, from its folder or its pack
+pub fn read_level_file(folder: &str, file: &str) -> Option<Vec<u8>> {
+    if is_pack(folder) {
+        read_pack_file(folder, file)
+    } else {
+        std::fs::read(config::assets_path().join("levels").join(folder).join(file)).ok()
+    }
+}
+
+pub fn level_has_file(folder: &str, file: &str) -> bool {
+    if is_pack(folder) {
+        pack_has_file(folder, file)
+    } else {
+        config::assets_path().join("levels").join(folder).join(file).is_file()
+    }
+}
+
+/// Default asset reader that also sees into `levels/*.zip`:
+/// `levels/<folder>/...` is read from the pack when there is no such folder.
+/// glTF resolves its textures next to itself, so they come from the same pack
+pub struct LevelPackAssetReader {
+    pub inner: Box<dyn AssetReader>,
+}
+
+impl LevelPackAssetReader {
+    /// `levels/<folder>/<file>` -> (folder, file) if that folder is a pack
+    fn in_pack(path: &Path) -> Option<(String, String)> {
+        let mut components = path.components();
+        if components.next()?.as_os_str() != "levels" {
+            return None;
+        }
+        let folder = components.next()?.as_os_str().to_string_lossy().into_owned();
+        if !is_pack(&folder) {
+            return None;
+        }
+        let file = components
+            .map(|p| p.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        Some((folder, file))
+    }
+}
+
+impl AssetReader for LevelPackAssetReader {
+    fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        let Some((folder, file)) = Self::in_pack(path) else {
+            return self.inner.read(path);
+        };
+        Box::pin(async move {
+            let bytes = read_pack_file(&folder, &file)
+                .ok_or_else(|| AssetReaderError::NotFound(path.to_owned()))?;
+            let reader: Box<Reader> = Box::new(VecReader::new(bytes));
+            Ok(reader)
+        })
+    }
+
+    fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
+        let Some((folder, file)) = Self::in_pack(path) else {
+            return self.inner.read_meta(path);
+        };
+        Box::pin(async move {
+            let bytes = read_pack_file(&folder, &format!("{}.meta", file))
+                .ok_or_else(|| AssetReaderError::NotFound(path.to_owned()))?;
+            let reader: Box<Reader> = Box::new(VecReader::new(bytes));
+            Ok(reader)
+        })
+    }
+
+    fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<Box<PathStream>, AssetReaderError>> {
+        let Some((folder, dir)) = Self::in_pack(path) else {
+            return self.inner.read_directory(path);
+        };
+        Box::pin(async move {
+            let paths = with_pack(&folder, |archive| Some(entries_under(archive, &folder, &dir)))
+                .ok_or_else(|| AssetReaderError::NotFound(path.to_owned()))?
+                .into_iter()
+                .map(|p| PathBuf::from("levels").join(&folder).join(p))
+                .collect::<Vec<_>>();
+            let stream: Box<PathStream> = Box::new(stream::iter(paths));
+            Ok(stream)
+        })
+    }
+
+    fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxedFuture<'a, Result<bool, AssetReaderError>> {
+        let Some((folder, dir)) = Self::in_pack(path) else {
+            return self.inner.is_directory(path);
+        };
+        Box::pin(async move {
+            let entries = with_pack(&folder, |archive| Some(entries_under(archive, &folder, &dir)))
+                .ok_or_else(|| AssetReaderError::NotFound(path.to_owned()))?;
+            Ok(dir.is_empty() || !entries.is_empty())
+        })
+    }
+}
+
+/// Replaces the default asset source, has to be registered before `AssetPlugin`.
+/// The watcher only does something with `--features dev`, packs are not watched
+pub fn asset_source() -> AssetSourceBuilder {
+    let mut default_reader = AssetSource::get_default_reader("assets".to_string());
+    AssetSource::build()
+        .with_reader(move || {
+            Box::new(LevelPackAssetReader {
+                inner: default_reader(),
+            })
+        })
+        .with_watcher(AssetSource::get_default_watcher(
+            "assets".to_string(),
+            Duration::from_millis(300),
+        ))
+}
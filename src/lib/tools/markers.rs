@@ -40,3 +40,9 @@ pub struct ExploredGLTFObjectMarker;
 // light
 #[derive(Component)]
 pub struct ExploredLightObjectMarker;
+
+//
+// UI spawned by actions, despawned with the level
+//
+#[derive(Component)]
+pub struct LevelUiMarker;
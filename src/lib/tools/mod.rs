@@ -4,4 +4,8 @@ pub mod events;
 pub mod markers;
 pub mod transition;
 pub mod resources;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod level_pack;pub mod text_input;
+pub mod answer;
+pub mod questions;
+pub mod formula;
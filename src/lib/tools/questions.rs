@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use bevy::utils::HashMap;
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{answer::AnswerTolerance, formula, level_pack};
+
+/// One entry of `questions.json` in the level folder, which is a list of them
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Question {
+    /// Unique in the file, questions are not repeated while others are left
+    pub id: String,
+    /// Shown next to the quiz, may be `@key`
+    pub text: String,
+    #[serde(default, rename = "type")]
+    pub kind: QuestionKind,
+    /// Right answers, for `choice` these are the right choices
+    pub answers: Vec<String>,
+    /// Options of a `choice` question, answered by their text or letter
+    #[serde(default)]
+    pub choices: Vec<String>,
+    /// Shown once the question is answered
+    #[serde(default)]
+    pub explanation: Option<String>,
+    /// 1 to 5
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Same as `#answer_tolerance` of test_chamber, for `number`
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    #[serde(default)]
+    pub tolerance_absolute: Option<f64>,
+    #[serde(default)]
+    pub unit_required: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    #[default]
+    Text,
+    Number,
+    Choice,
+}
+
+impl Question {
+    /// "A", "B", ... for the choice at that index
+    pub fn choice_letter(index: usize) -> char {
+        (b'A' + (index % 26) as u8) as char
+    }
+
+    pub fn tolerance(&self, base: AnswerTolerance) -> AnswerTolerance {
+        AnswerTolerance {
+            numeric: self.kind == QuestionKind::Number,
+            relative: self.tolerance.unwrap_or(base.relative),
+            absolute: self.tolerance_absolute.unwrap_or(base.absolute),
+            unit_required: self.unit_required || base.unit_required,
+            ..base
+        }
+    }
+
+    /// A typed letter of a `choice` question stands for its text
+    pub fn expand_choice<'a>(&'a self, given: &'a str) -> &'a str {
+        if self.kind != QuestionKind::Choice {
+            return given;
+        }
+        let letter = given.trim();
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(p), None) => self
+                .choices
+                .iter()
+                .enumerate()
+                .find(|(i, _)| Self::choice_letter(*i).eq_ignore_ascii_case(&p))
+                .map(|p| p.1.as_str())
+                .unwrap_or(given),
+            _ => given,
+        }
+    }
+
+    pub fn matches(&self, tags: &[String], difficulty: Option<(u8, u8)>) -> bool {
+        fits(&self.tags, self.difficulty, tags, difficulty)
+    }
+}
+
+/// Has one of `tags` (any if empty) and difficulty in range, no difficulty fits any range
+fn fits(own_tags: &[String], own_difficulty: Option<u8>, tags: &[String], difficulty: Option<(u8, u8)>) -> bool {
+    (tags.is_empty() || own_tags.iter().any(|p| tags.contains(p)))
+        && difficulty.map_or(true, |(min, max)| {
+            own_difficulty.map_or(true, |p| (min..=max).contains(&p))
+        })
+}
+
+/// One entry of `problems.json` in the level folder: a numeric question
+/// with values rolled each time and the answer computed from them
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ProblemTemplate {
+    pub id: String,
+    /// `{R}` is replaced with the rolled value of R, may be `@key:{U}|{R}`
+    pub text: String,
+    /// Rolled in name order, so a seed always gives the same values
+    pub variables: BTreeMap<String, VariableRange>,
+    /// "U / R", see `formula::evaluate`, variables are in the units of the text
+    pub formula: String,
+    /// Unit of the formula's result, "A"
+    #[serde(default)]
+    pub unit: String,
+    /// `{answer}` and the variables are replaced here too
+    #[serde(default)]
+    pub explanation: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    #[serde(default)]
+    pub tolerance_absolute: Option<f64>,
+    #[serde(default)]
+    pub unit_required: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct VariableRange {
+    pub min: f64,
+    pub max: f64,
+    /// Values are `min` plus a multiple of it, 1 by default
+    #[serde(default)]
+    pub step: Option<f64>,
+}
+
+impl VariableRange {
+    pub fn roll(&self, rng: &mut StdRng) -> f64 {
+        let step = self.step.filter(|p| *p > 0.).unwrap_or(1.);
+        let steps = ((self.max - self.min) / step).floor().max(0.) as u64;
+        let value = self.min + step * rng.gen_range(0..=steps) as f64;
+        // 0.1 * 3 is 0.30000000000000004. Rounding to a multiple of step
+        // could leave the range when min is not one, so digits are cut instead
+        let value = format!("{:.12e}", value).parse::<f64>().unwrap_or(value);
+        value.clamp(self.min, self.max.max(self.min))
+    }
+}
+
+/// Short form of a rolled or computed value, 4 significant digits: 0.5, 12, 0.3333, 0.000002
+pub fn format_value(value: f64) -> String {
+    if value == 0. || !value.is_finite() {
+        return value.to_string();
+    }
+    let decimals = (3 - value.abs().log10().floor() as i32).max(0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        text
+    }
+}
+
+impl ProblemTemplate {
+    pub fn matches(&self, tags: &[String], difficulty: Option<(u8, u8)>) -> bool {
+        fits(&self.tags, self.difficulty, tags, difficulty)
+    }
+
+    /// Rolls the values and turns it into an ordinary number question
+    pub fn roll(&self, rng: &mut StdRng) -> Option<Question> {
+        let values = self
+            .variables
+            .iter()
+            .map(|(name, range)| (name.clone(), range.roll(rng)))
+            .collect::<HashMap<_, _>>();
+        let answer = match formula::evaluate(&self.formula, &values) {
+            Ok(p) if p.is_finite() => p,
+            Ok(p) => {
+                println!("Problem {} came out as {} with {:?}", self.id, p, values);
+                return None;
+            }
+            Err(err) => {
+                println!("Formula of problem {} is broken: {}", self.id, err);
+                return None;
+            }
+        };
+
+        let fill = |text: &str| {
+            let mut text = text.replace("{answer}", &format_value(answer));
+            for (name, value) in values.iter() {
+                text = text.replace(&format!("{{{}}}", name), &format_value(*value));
+            }
+            text
+        };
+        Some(Question {
+            id: self.id.clone(),
+            text: fill(&self.text),
+            kind: QuestionKind::Number,
+            // unrounded, tolerance takes care of the student's rounding
+            answers: vec![format!("{:e} {}", answer, self.unit)],
+            choices: vec![],
+            explanation: self.explanation.as_deref().map(fill),
+            difficulty: self.difficulty,
+            tags: self.tags.clone(),
+            tolerance: self.tolerance,
+            tolerance_absolute: self.tolerance_absolute,
+            unit_required: self.unit_required,
+        })
+    }
+}
+
+/// `file` of the level folder as a list, empty if there is none
+pub fn read_list<T: serde::de::DeserializeOwned>(folder: &str, file: &str) -> Vec<T> {
+    let Some(bytes) = level_pack::read_level_file(folder, file) else {
+        return vec![];
+    };
+    match serde_json::from_slice::<Vec<T>>(&bytes) {
+        Ok(p) => p,
+        Err(err) => {
+            println!("{} of {} is broken: {}", file, folder, err);
+            vec![]
+        }
+    }
+}
+
+pub fn read_questions(folder: &str) -> Vec<Question> {
+    read_list(folder, "questions.json")
+}
+
+pub fn read_problems(folder: &str) -> Vec<ProblemTemplate> {
+    read_list(folder, "problems.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn rolls(min: f64, max: f64, step: f64) -> Vec<f64> {
+        let range = VariableRange {
+            min,
+            max,
+            step: Some(step),
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..200).map(|_| range.roll(&mut rng)).collect()
+    }
+
+    #[test]
+    fn rolls_stay_in_range() {
+        for value in rolls(1.25, 2.25, 0.5) {
+            assert!((1.25..=2.25).contains(&value), "{} is out of range", value);
+            assert!([1.25, 1.75, 2.25].contains(&value), "{} is not on a step", value);
+        }
+        for value in rolls(0.3, 1., 0.25) {
+            assert!((0.3..=1.).contains(&value), "{} is out of range", value);
+        }
+    }
+
+    #[test]
+    fn rolls_are_short() {
+        let values = rolls(0., 1., 0.1);
+        for value in values.iter() {
+            assert!(value.to_string().len() <= 3, "{} is not cut", value);
+        }
+        assert!(values.contains(&0.3));
+        assert!(values.contains(&1.));
+    }
+
+    #[test]
+    fn empty_range_rolls_min() {
+        assert!(rolls(5., 5., 1.).iter().all(|p| *p == 5.));
+        assert!(rolls(5., 4., 1.).iter().all(|p| *p == 5.));
+    }
+}
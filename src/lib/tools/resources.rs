@@ -1,14 +1,74 @@
-use bevy::{prelude::{Resource, Entity, Handle}, gltf::Gltf};
+use std::time::Duration;
+
+use bevy::{prelude::{Resource, Entity, Handle}, gltf::Gltf, utils::{HashMap, HashSet}};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Serialize, Deserialize};
 
 use crate::lib::main_menu::components::MainMenuVariants;
 
-use super::transition::TransitionMarker;
+use super::{config::{AnimationMarkersSchema, LevelSchema}, questions::{ProblemTemplate, Question, QuestionKind}, transition::TransitionMarker};
 
 #[derive(Resource, Serialize, Deserialize)]
 pub struct AllSettings {
     pub volume: f64,
     pub fov: f32,
+    /// "uk" | "en", picks string tables from assets/lang
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+fn default_language() -> String {
+    "uk".into()
+}
+
+/// String tables of the chosen language: assets/lang/<language>.json
+/// and levels/<level>/lang/<language>.json of the loaded level, which wins
+#[derive(Resource, Default)]
+pub struct Locale {
+    pub language: String,
+    pub ui: HashMap<String, String>,
+    pub level: HashMap<String, String>,
+    /// Level the `level` table is from
+    pub level_name: String,
+}
+
+impl Locale {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.level
+            .get(key)
+            .or_else(|| self.ui.get(key))
+            .map(|p| p.as_str())
+    }
+
+    /// The key itself if neither table has it
+    pub fn tr(&self, key: &str) -> String {
+        self.get(key).unwrap_or(key).to_owned()
+    }
+
+    /// Text shown to player: plain text stays as it is,
+    /// `@key` is looked up and `@key:a|b` also fills `{}` of the string with a and b in order.
+    /// Arguments starting with @ are looked up too
+    pub fn resolve(&self, text: &str) -> String {
+        let Some(reference) = text.strip_prefix('@') else {
+            return text.to_owned();
+        };
+        let (key, args) = match reference.split_once(':') {
+            Some((key, args)) => (key, Some(args)),
+            None => (reference, None),
+        };
+        let mut out = self.tr(key);
+        for arg in args.into_iter().flat_map(|p| p.split('|')) {
+            let arg = match arg.strip_prefix('@') {
+                Some(key) => self.tr(key),
+                None => arg.to_owned(),
+            };
+            match out.find("{}") {
+                Some(at) => out.replace_range(at..at + 2, &arg),
+                None => out.push_str(&arg),
+            }
+        }
+        out
+    }
 }
 
 #[derive(Resource)]
@@ -30,4 +90,221 @@ pub struct LoadingSceneInfo {
     pub name: String,
     pub handle: Handle<Gltf>, 
     pub is_loaded: bool
-}
\ No newline at end of file
+}
+
+/// Answers given in test chambers of the current level
+#[derive(Resource, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Score {
+    pub right: u32,
+    pub wrong: u32,
+}
+
+impl Score {
+    /// More right answers first, then fewer wrong ones
+    pub fn is_better_than(&self, other: &Score) -> bool {
+        (self.right, other.wrong) > (other.right, self.wrong)
+    }
+}
+
+/// What is known about one level folder
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LevelProgress {
+    #[serde(default)]
+    pub completed: bool,
+    /// Seconds from entering the level till completing it
+    #[serde(default)]
+    pub best_time: Option<f32>,
+    #[serde(default)]
+    pub best_score: Option<Score>,
+    /// Where player comes back to, cleared once the level is completed
+    #[serde(default)]
+    pub checkpoint: Option<[f32; 3]>,
+}
+
+/// `progress.json` in the data folder, level folder -> progress
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct Progress {
+    #[serde(default)]
+    pub levels: HashMap<String, LevelProgress>,
+}
+
+impl Progress {
+    pub fn is_completed(&self, level: &str) -> bool {
+        self.levels.get(level).is_some_and(|p| p.completed)
+    }
+
+    pub fn completed(&self) -> HashSet<String> {
+        self.levels
+            .iter()
+            .filter(|p| p.1.completed)
+            .map(|p| p.0.clone())
+            .collect()
+    }
+
+    /// Keeps the best time and score
+    pub fn complete(&mut self, level: &str, time: f32, score: Score) {
+        let entry = self.levels.entry(level.to_owned()).or_default();
+        entry.completed = true;
+        entry.checkpoint = None;
+        if entry.best_time.map_or(true, |p| time < p) {
+            entry.best_time = Some(time);
+        }
+        if entry.best_score.map_or(true, |p| score.is_better_than(&p)) {
+            entry.best_score = Some(score);
+        }
+    }
+
+    pub fn set_checkpoint(&mut self, level: &str, position: [f32; 3]) {
+        self.levels.entry(level.to_owned()).or_default().checkpoint = Some(position);
+    }
+}
+
+/// `Time::elapsed` when the current level was entered
+#[derive(Resource, Default)]
+pub struct LevelStartTime(pub Duration);
+
+/// Where `action:load_level` goes, and what it takes along
+pub struct PendingLevel {
+    pub level: String,
+    pub inventory: Option<Vec<InventoryItem>>,
+    pub score: Option<Score>,
+    /// LoadLevel was already sent
+    pub requested: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct LevelTransition {
+    pub pending: Option<PendingLevel>,
+}
+
+/// `config.json` of the loaded level
+#[derive(Resource, Default)]
+pub struct CurrentLevelConfig(pub LevelSchema);
+
+/// `questions.json` and `problems.json` of the loaded level, drawn from by `action:quiz` and `action:problem`
+#[derive(Resource)]
+pub struct QuestionBank {
+    pub questions: Vec<Question>,
+    pub problems: Vec<ProblemTemplate>,
+    /// Ids already drawn, cleared for a tag once all its questions are asked
+    pub asked: HashSet<String>,
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl QuestionBank {
+    pub fn new(questions: Vec<Question>, problems: Vec<ProblemTemplate>, seed: u64) -> QuestionBank {
+        QuestionBank {
+            questions,
+            problems,
+            asked: HashSet::default(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// A question with one of `tags` (any if empty), difficulty in range
+    /// and of that kind if one is given, not asked yet if possible
+    pub fn draw(&mut self, tags: &[String], difficulty: Option<(u8, u8)>, kind: Option<QuestionKind>) -> Option<Question> {
+        pick(
+            &self.questions,
+            |p| &p.id,
+            |p| p.matches(tags, difficulty) && kind.map_or(true, |k| p.kind == k),
+            &mut self.asked,
+            &mut self.rng,
+        )
+        .cloned()
+    }
+
+    /// Same as `draw`, for problems. Values are rolled anew each time
+    pub fn draw_problem(&mut self, tags: &[String], difficulty: Option<(u8, u8)>) -> Option<Question> {
+        pick(
+            &self.problems,
+            |p| &p.id,
+            |p| p.matches(tags, difficulty),
+            &mut self.asked,
+            &mut self.rng,
+        )?
+        .roll(&mut self.rng)
+    }
+}
+
+/// One of `items` that `fits`, not in `asked` if possible. Once all that fit are asked,
+/// they are dropped from `asked` and drawn among again
+fn pick<'a, T>(
+    items: &'a [T],
+    id: impl Fn(&T) -> &String,
+    fits: impl Fn(&T) -> bool,
+    asked: &mut HashSet<String>,
+    rng: &mut StdRng,
+) -> Option<&'a T> {
+    let candidates = items.iter().filter(|p| fits(p)).collect::<Vec<_>>();
+    let mut fresh = candidates
+        .iter()
+        .filter(|p| !asked.contains(id(p)))
+        .copied()
+        .collect::<Vec<_>>();
+    if fresh.is_empty() {
+        for p in candidates.iter() {
+            asked.remove(id(p));
+        }
+        fresh = candidates;
+    }
+    if fresh.is_empty() {
+        return None;
+    }
+    let item = fresh[rng.gen_range(0..fresh.len())];
+    asked.insert(id(item).clone());
+    Some(item)
+}
+
+impl Default for QuestionBank {
+    fn default() -> Self {
+        QuestionBank::new(vec![], vec![], 0)
+    }
+}
+
+/// Markers from `animation_markers.json` of the loaded level
+#[derive(Resource, Default)]
+pub struct AnimationMarkers(pub AnimationMarkersSchema);
+
+#[derive(Clone)]
+pub struct InventoryItem {
+    pub id: String,
+    pub name: String,
+    /// Path to an image, relative to assets
+    pub icon: Option<String>,
+    pub count: u32,
+}
+
+/// Items player picked up in the current level
+#[derive(Resource, Default)]
+pub struct Inventory {
+    pub items: Vec<InventoryItem>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: InventoryItem) {
+        if let Some(held) = self.items.iter_mut().find(|p| p.id == item.id) {
+            held.count += item.count;
+        } else {
+            self.items.push(item);
+        }
+    }
+
+    pub fn has(&self, id: &str) -> bool {
+        self.items.iter().any(|p| p.id == id && p.count > 0)
+    }
+
+    /// Removes one of the items, `false` if there was none
+    pub fn take(&mut self, id: &str) -> bool {
+        let Some(index) = self.items.iter().position(|p| p.id == id && p.count > 0) else {
+            return false;
+        };
+        self.items[index].count -= 1;
+        if self.items[index].count == 0 {
+            self.items.remove(index);
+        }
+        true
+    }
+}
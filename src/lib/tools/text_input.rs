@@ -0,0 +1,156 @@
+use bevy::prelude::{Input, KeyCode};
+
+/// Text typed into an input field, caret and selection are in chars, not bytes
+#[derive(Default, Clone)]
+pub struct TextInput {
+    pub text: String,
+    pub caret: usize,
+    /// Other end of the selection, if something is selected
+    pub anchor: Option<usize>,
+}
+
+impl TextInput {
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map(|p| p.0)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Selected range, start < end
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.anchor
+            .filter(|p| *p != self.caret)
+            .map(|p| (p.min(self.caret), p.max(self.caret)))
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection()
+            .map(|(start, end)| &self.text[self.byte_index(start)..self.byte_index(end)])
+    }
+
+    /// Removes the selection, true if there was one
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.text.replace_range(range, "");
+        self.caret = start;
+        self.anchor = None;
+        true
+    }
+
+    /// Typed or pasted text replaces the selection.
+    /// Line breaks and other control characters are dropped
+    pub fn insert(&mut self, text: &str) {
+        let text = text.chars().filter(|p| !p.is_control()).collect::<String>();
+        if text.is_empty() {
+            return;
+        }
+        self.delete_selection();
+        let at = self.byte_index(self.caret);
+        self.text.insert_str(at, &text);
+        self.caret += text.chars().count();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.delete_selection() || self.caret == 0 {
+            return;
+        }
+        self.caret -= 1;
+        let at = self.byte_index(self.caret);
+        self.text.remove(at);
+    }
+
+    pub fn delete(&mut self) {
+        if self.delete_selection() || self.caret >= self.len() {
+            return;
+        }
+        let at = self.byte_index(self.caret);
+        self.text.remove(at);
+    }
+
+    /// Moves the caret, with `select` the selection grows instead of being dropped
+    pub fn move_to(&mut self, caret: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = caret.min(self.len());
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.len();
+    }
+
+    pub fn clear(&mut self) {
+        *self = TextInput::default();
+    }
+
+    /// Arrows, Home, End, Backspace, Delete, Ctrl+A and Ctrl+V.
+    /// Characters themselves come from `ReceivedCharacter`
+    pub fn handle_keys(&mut self, keys: &Input<KeyCode>) {
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let ctrl = keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+
+        if keys.just_pressed(KeyCode::Left) {
+            match (self.selection(), shift) {
+                (Some((start, _)), false) => self.move_to(start, false),
+                _ => self.move_to(self.caret.saturating_sub(1), shift),
+            }
+        }
+        if keys.just_pressed(KeyCode::Right) {
+            match (self.selection(), shift) {
+                (Some((_, end)), false) => self.move_to(end, false),
+                _ => self.move_to(self.caret + 1, shift),
+            }
+        }
+        if keys.just_pressed(KeyCode::Home) {
+            self.move_to(0, shift);
+        }
+        if keys.just_pressed(KeyCode::End) {
+            self.move_to(self.len(), shift);
+        }
+        if keys.just_pressed(KeyCode::Back) {
+            self.backspace();
+        }
+        if keys.just_pressed(KeyCode::Delete) {
+            self.delete();
+        }
+        if ctrl && keys.just_pressed(KeyCode::A) {
+            self.select_all();
+        }
+        if ctrl && keys.just_pressed(KeyCode::V) {
+            match arboard::Clipboard::new().and_then(|mut p| p.get_text()) {
+                Ok(text) => self.insert(&text),
+                Err(err) => println!("Nothing to paste: {}", err),
+            }
+        }
+    }
+
+    /// (text before, selected text, text after) and whether the caret is before the selection
+    pub fn parts(&self) -> (&str, &str, &str, bool) {
+        let (start, end) = self.selection().unwrap_or((self.caret, self.caret));
+        let (start, end) = (self.byte_index(start), self.byte_index(end));
+        (
+            &self.text[..start],
+            &self.text[start..end],
+            &self.text[end..],
+            self.anchor.is_some_and(|p| p > self.caret),
+        )
+    }
+}
@@ -8,6 +8,7 @@ use bevy::{
     prelude::*,
     // render::{settings::WgpuFeatures, RenderPlugin},
      pbr::DefaultOpaqueRendererMethod,
+    asset::io::AssetSourceId,
 };
 // use std::time::Duration;
 
@@ -27,16 +28,18 @@ use bevy_rapier3d::{
     prelude::{NoUserData, RapierPhysicsPlugin},
     // render::RapierDebugRenderPlugin,
 };
-use lib::{tools::{events, resources::AllSettings}, *};
+use lib::{tools::{events, level_pack, resources::{self, AllSettings}}, *};
 
 fn main() {
     // std::env::set_var("RUST_BACKTRACE", "full");
     // println!("{:?}", std::env::var_os("CARGO_MANIFEST_DIR"));
 
     App::new()
+        // levels/*.zip are read as if they were folders
+        .register_asset_source(AssetSourceId::Default, level_pack::asset_source())
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
-                title: "Подорож з фізикою".into(),
+                title: localization::initial_window_title(),
                 ..default()
             }),
             ..default()
@@ -58,7 +61,17 @@ fn main() {
         .insert_resource(AllSettings { 
             volume: 1.0,
             fov: 90.,
+            language: "uk".into(),
         })
+        .init_resource::<resources::Locale>()
+        .init_resource::<resources::Inventory>()
+        .init_resource::<resources::AnimationMarkers>()
+        .init_resource::<resources::QuestionBank>()
+        .init_resource::<resources::CurrentLevelConfig>()
+        .init_resource::<resources::Score>()
+        .init_resource::<resources::Progress>()
+        .init_resource::<resources::LevelStartTime>()
+        .init_resource::<resources::LevelTransition>()
         //
         .add_state::<GameState>()
         .add_state::<UiState>()
@@ -85,6 +98,8 @@ fn main() {
             audio::AudioPlayerPlugin,
             broadcast::ManagerPlugin {},
             hint_overlay::HintOverlayPlugin,
+            interaction::InteractionPlugin,
+            localization::LocalizationPlugin,
         ))
         //
         .add_systems(Startup, settings)